@@ -0,0 +1 @@
+window.REWRITTEN_CONFIG = {"API_HOST":"{{BASE}}","CPUI_URL":"{{BASE}}cpui","IN_PRODUCT_MESSAGING_URL":"","CAREERS2_UI_URL":"","HUBSUI_URL":"","EVENTSUI_URL":"","SCHOLARSHIPSUI_URL":"{{BASE}}scholarships","NAVIANCESCHOLARSHIPSUI_URL":"","NAVIANCESURVEYSUI_URL":"","SUPERMATCHUI_URL":"{{BASE}}supermatch","ACTIVEMATCHUI_URL":"","FEEDBACK_URL":"","RECAPTCHA_SITE_KEY":"mock-recaptcha","INLINE_MANUAL_URL":"","COMMON_APP_BASE_URL":"","HEAP_ANALYTICS_API_CODE":1234,"READINESS_INDICATORS_UI_URL":"","PHRASE_BATCH_LIMIT":100,"HEADED2_UI_URL":"","HEADED2_TOKEN_EXCHANGE_URL":"","LOCAL_OPPORTUNITIES_URL":"","HEADED2_API_DOMAIN":"","HEADED2_APP_BASE_URL":"","MY_PATHWAYS_UI_URL":"","MY_PORTFOLIO_UI_URL":"","UNIFIED_USER_LOGIN_URL":"{{BASE}}login","INGEST_RAW_EVENT_CTA_APP_BASE_URL":"","PORTAL_API_HOST":"{{BASE}}portal","APPILY_MATCH_UI_URL":"","APPILY_MATCH_API_URL":"","CHATTERBOX_UI_URL":"","ACE_CHATBOT_UI_URL":"","DS_API_URL":"","DS_API_TEMP_TOKEN":""};
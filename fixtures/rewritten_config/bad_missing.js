@@ -0,0 +1 @@
+window.SOMETHING_ELSE = {"API_HOST": "https://api.example.com/"};
@@ -0,0 +1 @@
+window.REWRITTEN_CONFIG = loadConfig();
@@ -0,0 +1 @@
+window.REWRITTEN_CONFIG = {"API_HOST": "https://api.example.com/",
@@ -0,0 +1,7 @@
+/* Generated at build time - do not edit */
+// window.REWRITTEN_CONFIG = {"API_HOST": "https://commented-out.example.com/"};
+window.REWRITTEN_CONFIG = {
+  // the API
+  "API_HOST": "https://api.example.com/", /* trailing */
+  "FEEDBACK_URL": "https://feedback.example.com/?a=1//not-a-comment"
+};
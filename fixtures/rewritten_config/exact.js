@@ -0,0 +1 @@
+window.REWRITTEN_CONFIG = {"API_HOST":"https://api.example.com/","UNIFIED_USER_LOGIN_URL":"https://login.example.com/"};
\ No newline at end of file
@@ -0,0 +1,5 @@
+(function (w) {
+  'use strict';
+  var pattern = /it's a regex/;
+  w.REWRITTEN_CONFIG = {"API_HOST": "https://api.example.com/"};
+})(window);
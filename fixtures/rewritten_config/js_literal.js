@@ -0,0 +1,7 @@
+window.REWRITTEN_CONFIG = {
+  API_HOST: 'https://api.example.com/',
+  UNIFIED_USER_LOGIN_URL: `https://login.example.com/`,
+  RECAPTCHA_SITE_KEY: 'it\'s "quoted"',
+  HEAP_ANALYTICS_API_CODE: undefined,
+  PHRASE_BATCH_LIMIT: 50,
+};
@@ -0,0 +1,5 @@
+window.FEATURE_FLAGS = {"darkMode": true, "nested": {"a": [1, 2, {"b": "}"}]}};
+window.REWRITTEN_CONFIG = {"API_HOST": "https://stale.example.com/"};
+if (window.REWRITTEN_CONFIG == null) { console.log("no config"); }
+window.REWRITTEN_CONFIG = {"API_HOST": "https://api.example.com/"};
+window.ANALYTICS = {"id": "UA-1234"};
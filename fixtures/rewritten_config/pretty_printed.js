@@ -0,0 +1,5 @@
+window.REWRITTEN_CONFIG = {
+  "API_HOST": "https://api.example.com/",
+  "UNIFIED_USER_LOGIN_URL": "https://login.example.com/",
+  "PHRASE_BATCH_LIMIT": 50
+};
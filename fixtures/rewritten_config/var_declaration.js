@@ -0,0 +1,2 @@
+var REWRITTEN_CONFIG = Object.freeze({"API_HOST": "https://api.example.com/"});
+window.REWRITTEN_CONFIG = REWRITTEN_CONFIG;
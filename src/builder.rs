@@ -0,0 +1,324 @@
+use std::{
+    path::PathBuf,
+    sync::{atomic::AtomicBool, Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use url::Url;
+
+use crate::{
+    cache::ResponseCache,
+    cassette::{Cassette, Tape},
+    coalesce::InFlight,
+    credentials::{CredentialProvider, Credentials, LoginKey},
+    drift::DriftReport,
+    login::{self, Login},
+    ratelimit::RateLimiter,
+    types::{Config, Endpoint, PublicEndpoint},
+    Client, ClientRef, Error, RateLimit, Result, RetryPolicy, SecretKey, STUDENT_BASE,
+};
+
+/// Builder for [`Client`], for when the defaults of [`Client::new`] don't cut
+/// it (local stand-in servers, staging hosts, proxies, ...)
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    key:             Option<SecretKey>,
+    student_base:    Option<Url>,
+    api_base:        Option<Url>,
+    timeout:         Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent:      Option<String>,
+    https_only:      Option<bool>,
+    http_client:     Option<reqwest::Client>,
+    retry:           Option<RetryPolicy>,
+    rate_limit:      Option<RateLimit>,
+    dump_dir:        Option<PathBuf>,
+    detect_drift:    bool,
+    strict:          bool,
+    dry_run:         bool,
+    tape:            Option<TapeSource>,
+    cache:           Option<ResponseCache>,
+    coalesce:        Option<bool>,
+    provider:        Option<Box<dyn CredentialProvider>>,
+    expiry_warning:  Option<Duration>,
+}
+
+#[derive(Debug)]
+enum TapeSource {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self { Self::default() }
+
+    /// The student's API key (bearer token)
+    pub fn key(mut self, key: impl Into<SecretKey>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Log in for a key instead of passing one in with
+    /// [`ClientBuilder::key`], and log in again whenever it's rejected.
+    /// Shorthand for [`ClientBuilder::credentials`] with a [`LoginKey`]
+    pub fn login(self, login: Login) -> Self { self.credentials(LoginKey::new(login)) }
+
+    /// Where to get a new key from when the current one expires or gets
+    /// rejected - also where the first one comes from if
+    /// [`ClientBuilder::key`] isn't set
+    pub fn credentials(mut self, provider: impl CredentialProvider) -> Self {
+        self.provider = Some(Box::new(provider));
+        self
+    }
+
+    /// Where to fetch `rewritten_config.js` from. Defaults to
+    /// `https://student.naviance.com/`
+    pub fn student_base(mut self, student_base: Url) -> Self {
+        self.student_base = Some(student_base);
+        self
+    }
+
+    /// Use this API base directly instead of discovering it from
+    /// `rewritten_config.js` (skips the config fetch entirely)
+    pub fn api_base(mut self, api_base: Url) -> Self {
+        self.api_base = Some(api_base);
+        self
+    }
+
+    /// Total timeout for each request
+    ///
+    /// Ignored if a custom HTTP client is set with
+    /// [`ClientBuilder::http_client`]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for the connect phase of each request
+    ///
+    /// Ignored if a custom HTTP client is set with
+    /// [`ClientBuilder::http_client`]
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Ignored if a custom HTTP client is set with
+    /// [`ClientBuilder::http_client`]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Only allow HTTPS connections (the default). Turn this off to talk to a
+    /// plain HTTP server on localhost
+    ///
+    /// Ignored if a custom HTTP client is set with
+    /// [`ClientBuilder::http_client`]
+    pub fn https_only(mut self, https_only: bool) -> Self {
+        self.https_only = Some(https_only);
+        self
+    }
+
+    /// Use a custom HTTP client
+    ///
+    /// Warning: You should probably use an HTTP client with HTTPS-only
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// How to retry failed requests. Defaults to [`RetryPolicy::default`]
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Limit request rate and concurrency across all clones of the client.
+    /// Unlimited by default
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Write any response body that fails to decode into this directory
+    pub fn debug_dump_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dump_dir = Some(dir.into());
+        self
+    }
+
+    /// Compare every response against our types and collect unknown, missing
+    /// and retyped fields into a [`DriftReport`](crate::drift::DriftReport)
+    ///
+    /// Off by default since it decodes every body twice
+    pub fn detect_drift(mut self, detect_drift: bool) -> Self {
+        self.detect_drift = detect_drift;
+        self
+    }
+
+    /// Decode strictly: unknown enum values, out-of-range booleans and
+    /// unknown fields become errors instead of being papered over. Meant for
+    /// regression tests, not production
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Don't send anything that would change data on Naviance (adding a
+    /// college to a list, ...). Write methods hand back a
+    /// [`DryRun`](crate::DryRun) showing the request instead. Reads still go
+    /// through
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Record every request and response (minus the API key) to a
+    /// [`Cassette`] at `path`, overwriting whatever's there. It's only written
+    /// on [`Client::save_cassette`]
+    pub fn record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.tape = Some(TapeSource::Record(path.into()));
+        self
+    }
+
+    /// Answer every request from the [`Cassette`] at `path` instead of the
+    /// network. Any key works
+    pub fn replay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.tape = Some(TapeSource::Replay(path.into()));
+        self
+    }
+
+    /// Keep slow-changing responses (college profiles, application
+    /// statistics, ...) on disk between runs
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Share one request between identical concurrent `GET`s, e.g. the same
+    /// college UUID fetched from several tasks at once. On by default
+    pub fn coalesce(mut self, coalesce: bool) -> Self {
+        self.coalesce = Some(coalesce);
+        self
+    }
+
+    /// Warn once the API key is this close to expiring (if it's a JWT with
+    /// an expiry). Defaults to 10 minutes
+    pub fn expiry_warning(mut self, expiry_warning: Duration) -> Self {
+        self.expiry_warning = Some(expiry_warning);
+        self
+    }
+
+    /// Where to send the browser to log in through `school`'s SSO provider.
+    /// Once it lands on `redirect_uri`, pass that URL to
+    /// [`Login::SsoRedirect`]
+    pub async fn sso_url(&self, school: &str, redirect_uri: &Url) -> Result<Url> {
+        let http_client = self.build_http_client()?;
+        let student_base = self
+            .student_base
+            .clone()
+            .unwrap_or_else(|| STUDENT_BASE.clone());
+        let req = Config::request(student_base.clone(), &http_client, &())?;
+        let response = http_client.execute(req).await?;
+        let body = Error::check_response(&student_base, Config::PATH, response)
+            .await?
+            .bytes()
+            .await?;
+        let login_base = login::login_base(&Config::extract(&body)?)?;
+        login::sso_url(&login_base, school, redirect_uri)
+    }
+
+    fn build_http_client(&self) -> Result<reqwest::Client> {
+        if let Some(http_client) = &self.http_client {
+            return Ok(http_client.clone());
+        }
+        let mut builder = reqwest::Client::builder()
+            .https_only(self.https_only.unwrap_or(true))
+            .use_rustls_tls()
+            .brotli(true)
+            .gzip(true)
+            .zstd(true)
+            .deflate(true);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        Ok(builder.build()?)
+    }
+
+    pub async fn build(self) -> Result<Client> {
+        let http_client = self.build_http_client()?;
+        let key = match (self.key, &self.provider) {
+            (Some(key), _) => key,
+            // Filled in by the provider below
+            (None, Some(_)) => SecretKey::new(""),
+            (None, None) => return Err(Error::Other("No API key provided".to_string())),
+        };
+        let student_base = self.student_base.unwrap_or_else(|| STUDENT_BASE.clone());
+        let tape = match self.tape {
+            Some(TapeSource::Record(path)) => Some(Tape::record(path)),
+            Some(TapeSource::Replay(path)) => Some(Tape::replay(Cassette::load(path)?)),
+            None => None,
+        };
+        let refreshable = self.provider.is_some();
+        let inner = Arc::new(ClientRef {
+            credentials: RwLock::new(Credentials::new(key)),
+            provider: self.provider,
+            refreshing: tokio::sync::Mutex::new(()),
+            login_base: None,
+            config: None,
+            // Replaced below once we know the real one
+            api_base: student_base.clone(),
+            retry: self.retry.unwrap_or_default(),
+            limiter: self.rate_limit.map(RateLimiter::new),
+            dump_dir: self.dump_dir,
+            drift: self
+                .detect_drift
+                .then(|| Mutex::new(DriftReport::default())),
+            strict: self.strict,
+            dry_run: self.dry_run,
+            tape,
+            cache: self.cache,
+            inflight: self.coalesce.unwrap_or(true).then(InFlight::default),
+            expiry_warning: self.expiry_warning.unwrap_or(Duration::from_secs(10 * 60)),
+            expiry_warned: AtomicBool::new(false),
+        });
+        let mut client = Client { http_client, inner };
+        // No point fetching the config with a key we know is dead, unless
+        // there's a new one coming
+        let needs_key = match client.check_key() {
+            Err(Error::KeyExpired { .. }) if refreshable => true,
+            checked => checked.map(|_| client.key().is_empty())?,
+        };
+        let config = match (&self.api_base, needs_key) {
+            (Some(_), false) => None,
+            // The provider might need the login URL
+            _ => {
+                let req = Config::request(student_base, &client.http_client, &())?;
+                Some(client.send::<Config>(req).await?)
+            }
+        };
+        let api_base = match (self.api_base, &config) {
+            (Some(api_base), _) => api_base,
+            (None, Some(config)) => config.api_host.clone().ok_or(Error::ConfigParse(
+                "No API host found in rewritten_config.js".to_string(),
+            ))?,
+            (None, None) => unreachable!("config is always fetched without an API base"),
+        };
+        // Nobody else has seen this client yet
+        let inner = Arc::get_mut(&mut client.inner).expect("client was cloned during construction");
+        inner.api_base = api_base;
+        inner.login_base = config.as_ref().and_then(|c| login::login_base(c).ok());
+        inner.config = config;
+        if needs_key {
+            client.refresh_key(&client.key()).await?;
+            client.check_key()?;
+        }
+        Ok(client)
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{types::Endpoint, Result};
+
+/// How the cache gets used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Serve fresh entries, revalidate or refetch stale ones
+    #[default]
+    Normal,
+    /// Never touch the network for cacheable endpoints - a miss is an
+    /// [`Error::NotCached`](crate::Error::NotCached)
+    CacheOnly,
+    /// Always refetch, then store the result
+    Refresh,
+}
+
+/// A persistent response cache, set with
+/// [`ClientBuilder::cache`](crate::ClientBuilder::cache)
+///
+/// Only `GET` endpoints with a TTL are cached - by default that's
+/// [`College`](crate::types::College) and
+/// [`ApplicationStatistics`](crate::types::ApplicationStatistics), see
+/// [`Endpoint::CACHE_TTL`]. Entries are keyed by path, query and who's asking
+/// (the key's `sub`, or a hash of the key if it doesn't have one), so one
+/// cache directory can be shared between students
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir:  PathBuf,
+    mode: CacheMode,
+    ttls: HashMap<&'static str, Option<Duration>>,
+}
+
+/// What's stored on disk per request
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    /// The [`Endpoint::PATH`] it came from, for
+    /// [`ResponseCache::purge_endpoint`]. Missing from older entries
+    #[serde(default)]
+    pub(crate) endpoint:      Option<String>,
+    pub(crate) url:           String,
+    pub(crate) stored_at:     DateTime<Utc>,
+    pub(crate) etag:          Option<String>,
+    pub(crate) last_modified: Option<String>,
+    pub(crate) body:          String,
+}
+
+impl Entry {
+    pub(crate) fn body(&self) -> Bytes { Bytes::from(self.body.clone()) }
+
+    pub(crate) fn is_fresh(&self, ttl: Duration) -> bool {
+        let age = Utc::now().signed_duration_since(self.stored_at);
+        age.to_std().is_ok_and(|age| age < ttl)
+    }
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir:  dir.into(),
+            mode: CacheMode::default(),
+            ttls: HashMap::new(),
+        }
+    }
+
+    pub fn mode(mut self, mode: CacheMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Overrides `T`'s [`Endpoint::CACHE_TTL`]. `None` turns caching off for
+    /// it
+    pub fn ttl<T: Endpoint>(mut self, ttl: Option<Duration>) -> Self {
+        self.ttls.insert(T::PATH, ttl);
+        self
+    }
+
+    pub fn dir(&self) -> &Path { &self.dir }
+
+    pub fn get_mode(&self) -> CacheMode { self.mode }
+
+    /// How long `T`'s responses stay fresh, if they're cached at all
+    pub(crate) fn ttl_for<T: Endpoint>(&self) -> Option<Duration> {
+        match self.ttls.get(T::PATH) {
+            Some(ttl) => *ttl,
+            None => T::CACHE_TTL,
+        }
+    }
+
+    /// Deletes every cached response
+    pub fn purge(&self) -> Result<()> { self.purge_matching(|_| true) }
+
+    /// Deletes every cached response for `T`. Entries written before they
+    /// recorded their endpoint are left alone - use [`Self::purge`] for those
+    pub fn purge_endpoint<T: Endpoint>(&self) -> Result<()> {
+        self.purge_matching(|path| {
+            let Ok(file) = std::fs::read(path) else {
+                return false;
+            };
+            serde_json::from_slice::<Entry>(&file)
+                .is_ok_and(|entry| entry.endpoint.as_deref() == Some(T::PATH))
+        })
+    }
+
+    fn purge_matching(&self, matches: impl Fn(&Path) -> bool) -> Result<()> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") && matches(&path) {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn load(&self, path: PathBuf) -> Option<Entry> {
+        let read = tokio::task::spawn_blocking({
+            let path = path.clone();
+            move || std::fs::read(path)
+        });
+        let file = read.await.ok()?.ok()?;
+        match serde_json::from_slice(&file) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Ignoring corrupt cache entry {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    pub(crate) async fn store(&self, path: PathBuf, entry: &Entry) {
+        let entry = match serde_json::to_vec(entry) {
+            Ok(entry) => entry,
+            Err(e) => return warn!("Failed to serialize cache entry {}: {e}", path.display()),
+        };
+        let dir = self.dir.clone();
+        let write = tokio::task::spawn_blocking(move || {
+            // Write then rename so concurrent readers never see half an entry
+            let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+            std::fs::create_dir_all(dir)
+                .and_then(|_| std::fs::write(&tmp, entry))
+                .and_then(|_| std::fs::rename(&tmp, &path))
+                .map_err(|e| (path, e))
+        });
+        match write.await {
+            Ok(Err((path, e))) => warn!("Failed to write cache entry {}: {e}", path.display()),
+            Err(e) => warn!("Failed to write cache entry: {e}"),
+            Ok(Ok(())) => {}
+        }
+    }
+
+    /// Drops the entry at `path`, if there is one
+    pub(crate) async fn remove(&self, path: PathBuf) {
+        let _ = tokio::task::spawn_blocking(move || std::fs::remove_file(path)).await;
+    }
+
+    /// e.g. `college_uuid_<uuid>.<identity>.json`, where `identity`
+    /// fingerprints whoever the key belongs to
+    pub(crate) fn path_for(&self, request: &reqwest::Request, identity: &str) -> PathBuf {
+        let url = request.url();
+        let mut key = url.path().to_string();
+        if let Some(query) = url.query() {
+            key.push('?');
+            key.push_str(query);
+        }
+        self.dir
+            .join(format!("{}.{identity}.json", file_stem(&key)))
+    }
+}
+
+fn file_stem(key: &str) -> String {
+    key.trim_matches('/')
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
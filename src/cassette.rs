@@ -0,0 +1,270 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use reqwest::ResponseBuilderExt;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use url::Url;
+
+use crate::{
+    secret::SecretKey,
+    types::{Config, Endpoint},
+    util::lock,
+    Error, Result,
+};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// A recorded session: every request the client made and what came back, in
+/// order. Written by [`ClientBuilder::record`](crate::ClientBuilder::record)
+/// and served back by [`ClientBuilder::replay`](crate::ClientBuilder::replay)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// One request/response pair. The API key never makes it in here
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method:       String,
+    pub url:          Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub status:       u16,
+    #[serde(default)]
+    pub headers:      BTreeMap<String, String>,
+    pub body:         String,
+}
+
+impl Interaction {
+    /// Same method, path, query and body - the host is ignored so a cassette
+    /// recorded against one API base still replays against another
+    fn matches(&self, request: &reqwest::Request, body: Option<&str>) -> bool {
+        self.method == request.method().as_str()
+            && self.url.path() == request.url().path()
+            && self.url.query() == request.url().query()
+            && self.request_body.as_deref() == body
+    }
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&file)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// What the client does with a cassette
+#[derive(Debug)]
+pub(crate) enum Tape {
+    /// Goes to the network and keeps everything for the cassette at `path`,
+    /// which only gets written on [`Tape::flush`]
+    Record {
+        path:     PathBuf,
+        cassette: Mutex<Cassette>,
+        /// How many interactions are already on disk
+        saved:    AtomicUsize,
+        /// Tokens other than the API key that responses have handed out
+        secrets:  Mutex<Vec<SecretKey>>,
+    },
+    /// Never touches the network
+    Replay {
+        cassette: Cassette,
+        played:   Mutex<Vec<bool>>,
+    },
+}
+
+impl Tape {
+    pub(crate) fn record(path: PathBuf) -> Self {
+        Self::Record {
+            path,
+            cassette: Mutex::default(),
+            saved: AtomicUsize::new(0),
+            secrets: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn replay(cassette: Cassette) -> Self {
+        let played = Mutex::new(vec![false; cassette.interactions.len()]);
+        Self::Replay { cassette, played }
+    }
+
+    pub(crate) fn is_replay(&self) -> bool { matches!(self, Self::Replay { .. }) }
+
+    /// Answers `request` from the cassette. Identical requests get their
+    /// recorded responses in order, and the last one again once those run
+    /// out
+    pub(crate) fn play(&self, key: &str, request: &reqwest::Request) -> Result<reqwest::Response> {
+        let Self::Replay { cassette, played } = self else {
+            unreachable!("only replaying tapes can play");
+        };
+        let body = request_body(request, key);
+        let mut played = lock(played);
+        let candidates: Vec<usize> = (0..cassette.interactions.len())
+            .filter(|&i| cassette.interactions[i].matches(request, body.as_deref()))
+            .collect();
+        let index = candidates
+            .iter()
+            .copied()
+            .find(|&i| !played[i])
+            .or(candidates.last().copied())
+            .ok_or_else(|| Error::NotRecorded {
+                method: request.method().to_string(),
+                url:    request.url().to_string(),
+            })?;
+        played[index] = true;
+        let interaction = &cassette.interactions[index];
+        let mut response = http::Response::builder()
+            .status(interaction.status)
+            .url(interaction.url.clone());
+        for (name, value) in &interaction.headers {
+            response = response.header(name, value);
+        }
+        let response = response
+            .body(interaction.body.clone())
+            .map_err(|e| Error::Other(format!("Invalid recorded response: {e}")))?;
+        Ok(response.into())
+    }
+
+    /// Writes the exchange to the cassette with the API key and any other
+    /// tokens redacted, handing back an equivalent response since reading the
+    /// original consumes it
+    pub(crate) async fn capture(
+        &self,
+        key: &str,
+        method: String,
+        request_body: Option<String>,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let Self::Record {
+            cassette, secrets, ..
+        } = self
+        else {
+            return Ok(response);
+        };
+        let (status, url, headers) = (
+            response.status(),
+            response.url().clone(),
+            response.headers().clone(),
+        );
+        let body = response.bytes().await?;
+
+        let mut replayed = http::Response::builder().status(status).url(url.clone());
+        for (name, value) in &headers {
+            replayed = replayed.header(name, value);
+        }
+        let replayed = replayed
+            .body(body.clone())
+            .map_err(|e| Error::Other(format!("Failed to rebuild response: {e}")))?;
+
+        // The config carries a DS API token of its own
+        if url.path().ends_with(Config::PATH) {
+            if let Some(token) = Config::extract(&body)
+                .ok()
+                .and_then(|c| c.ds_api_temp_token)
+            {
+                lock(secrets).push(token);
+            }
+        }
+        let secrets = lock(secrets).clone();
+        let redact = |text: &str| {
+            secrets.iter().fold(redact(text, key), |text, secret| {
+                redact(&text, secret.expose())
+            })
+        };
+        let interaction = Interaction {
+            method,
+            url: Url::parse(&redact(url.as_str())).unwrap_or(url),
+            request_body: request_body.as_deref().map(redact),
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                // Redaction can change the length, and cookies are secrets too
+                .filter(|(name, _)| {
+                    ![
+                        http::header::SET_COOKIE,
+                        http::header::CONTENT_LENGTH,
+                        http::header::TRANSFER_ENCODING,
+                    ]
+                    .contains(name)
+                })
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.into())))
+                .collect(),
+            body: redact(&String::from_utf8_lossy(&body)),
+        };
+        lock(cassette).interactions.push(interaction);
+        Ok(replayed.into())
+    }
+
+    /// Writes everything recorded so far to the cassette file, off the
+    /// runtime. Does nothing when replaying
+    pub(crate) async fn flush(&self) -> Result<()> {
+        let Self::Record {
+            path,
+            cassette,
+            saved,
+            ..
+        } = self
+        else {
+            return Ok(());
+        };
+        let cassette = lock(cassette).clone();
+        let (path, len) = (path.clone(), cassette.interactions.len());
+        tokio::task::spawn_blocking(move || cassette.save(path))
+            .await
+            .map_err(|e| Error::Other(format!("Failed to save cassette: {e}")))??;
+        saved.fetch_max(len, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl Drop for Tape {
+    fn drop(&mut self) {
+        let Self::Record {
+            path,
+            cassette,
+            saved,
+            ..
+        } = self
+        else {
+            return;
+        };
+        // No file I/O here - this runs on whichever task drops the last client
+        let unsaved = lock(cassette).interactions.len() - *saved.get_mut();
+        if unsaved > 0 {
+            warn!(
+                "{unsaved} recorded interactions never made it to {} - call Client::save_cassette \
+                 before dropping the client",
+                path.display()
+            );
+        }
+    }
+}
+
+/// The request body as text with `key` redacted, if there is one we can see
+pub(crate) fn request_body(request: &reqwest::Request, key: &str) -> Option<String> {
+    let body = String::from_utf8_lossy(request.body()?.as_bytes()?);
+    Some(redact(&body, key))
+}
+
+fn redact(text: &str, key: &str) -> String {
+    match key.is_empty() {
+        true => text.to_string(),
+        false => text.replace(key, REDACTED),
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use futures::{
+    channel::oneshot,
+    future::{BoxFuture, Shared},
+    FutureExt,
+};
+
+use crate::{util::lock, Result};
+
+/// Resolves to the leader's body, or `None` if it failed or was dropped
+type Pending = Shared<BoxFuture<'static, Option<Bytes>>>;
+
+/// Single-flight table for identical concurrent requests
+///
+/// The first caller for a key (the leader) does the actual fetch, everyone
+/// who shows up while it's in flight waits for its body instead. Errors
+/// aren't shared - if the leader fails, each follower fetches on its own
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    pending: Arc<Mutex<HashMap<String, Pending>>>,
+}
+
+/// Takes the leader's key out of the table once it's done, even if it gets
+/// cancelled
+struct Leader {
+    key:     String,
+    pending: Arc<Mutex<HashMap<String, Pending>>>,
+}
+
+impl Drop for Leader {
+    fn drop(&mut self) { lock(&self.pending).remove(&self.key); }
+}
+
+impl InFlight {
+    /// Runs `fetch` unless an identical request is already in flight, in
+    /// which case its body is reused
+    pub(crate) async fn run<F, Fut>(&self, key: String, fetch: F) -> Result<Bytes>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Bytes>>,
+    {
+        let joined = {
+            let mut pending = lock(&self.pending);
+            match pending.get(&key) {
+                Some(leader) => Err(leader.clone()),
+                None => {
+                    let (tx, rx) = oneshot::channel();
+                    pending.insert(key.clone(), rx.map(|body| body.ok()).boxed().shared());
+                    Ok(tx)
+                }
+            }
+        };
+        match joined {
+            Ok(tx) => {
+                let _leader = Leader {
+                    key,
+                    pending: self.pending.clone(),
+                };
+                let result = fetch().await;
+                if let Ok(body) = &result {
+                    let _ = tx.send(body.clone());
+                }
+                result
+            }
+            Err(leader) => match leader.await {
+                Some(body) => Ok(body),
+                None => fetch().await,
+            },
+        }
+    }
+}
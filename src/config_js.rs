@@ -0,0 +1,271 @@
+//! Digging the config object out of `rewritten_config.js`
+//!
+//! The file is meant to be a single `window.REWRITTEN_CONFIG = {...};`, but
+//! it's JavaScript generated by someone else's build, so we don't count on
+//! the exact formatting. We look for the last assignment to
+//! `REWRITTEN_CONFIG` anywhere in the file, take the object literal on the
+//! right and turn it into JSON (comments, single quotes, bare keys and
+//! trailing commas are all fine)
+
+use std::borrow::Cow;
+
+use crate::{util::snippet, Error, Result};
+
+const NAME: &str = "REWRITTEN_CONFIG";
+
+/// The config object in `src`, as JSON
+pub(crate) fn extract(src: &str) -> Result<Cow<'_, str>> {
+    let start = find_assignment(src)?.ok_or_else(|| {
+        Error::ConfigParse(format!(
+            "No `{NAME} = {{...}}` assignment found in config.js: {}",
+            snippet(src.as_bytes())
+        ))
+    })?;
+    let end = object_end(src, start)?;
+    to_json(&src[start..end], start, src)
+}
+
+/// Where the object literal of the last `REWRITTEN_CONFIG = {` starts
+fn find_assignment(src: &str) -> Result<Option<usize>> {
+    let bytes = src.as_bytes();
+    let mut found = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(end) = skip_comment(src, i)? {
+            i = end;
+            continue;
+        }
+        match bytes[i] {
+            quote @ (b'"' | b'\'' | b'`') => {
+                // Could be a quote inside a regex - not our problem unless
+                // it's in the config object itself
+                let Ok(end) = skip_string(src, i) else {
+                    i += 1;
+                    continue;
+                };
+                // window["REWRITTEN_CONFIG"] = ...
+                if bytes[i + 1..end - 1] == *NAME.as_bytes() && quote != b'`' {
+                    let after = skip_trivia(src, end)?;
+                    if bytes.get(after) == Some(&b']') {
+                        found = assigned_object(src, after + 1)?.or(found);
+                    }
+                }
+                i = end;
+            }
+            c if is_ident(c) => {
+                let end = i + bytes[i..].iter().take_while(|&&c| is_ident(c)).count();
+                // Property names (`window.REWRITTEN_CONFIG`) count too
+                if &src[i..end] == NAME {
+                    found = assigned_object(src, end)?.or(found);
+                }
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    Ok(found)
+}
+
+/// If `src[i..]` is ` = {` (possibly wrapped, e.g. `= Object.freeze({`), where
+/// the `{` is
+fn assigned_object(src: &str, i: usize) -> Result<Option<usize>> {
+    let bytes = src.as_bytes();
+    let i = skip_trivia(src, i)?;
+    // `==` and `===` are comparisons, not assignments
+    if bytes.get(i) != Some(&b'=') || bytes.get(i + 1) == Some(&b'=') {
+        return Ok(None);
+    }
+    let mut i = skip_trivia(src, i + 1)?;
+    let callee = bytes[i..]
+        .iter()
+        .take_while(|&&c| is_ident(c) || c == b'.')
+        .count();
+    if callee > 0 {
+        i = skip_trivia(src, i + callee)?;
+        if bytes.get(i) != Some(&b'(') {
+            return Ok(None);
+        }
+        i = skip_trivia(src, i + 1)?;
+    }
+    Ok((bytes.get(i) == Some(&b'{')).then_some(i))
+}
+
+/// Just past the `}` closing the object that opens at `start`
+fn object_end(src: &str, start: usize) -> Result<usize> {
+    let bytes = src.as_bytes();
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        if let Some(end) = skip_comment(src, i)? {
+            i = end;
+            continue;
+        }
+        match bytes[i] {
+            b'"' | b'\'' | b'`' => i = skip_string(src, i)?,
+            b'{' | b'[' => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' | b']' => {
+                depth -= 1;
+                i += 1;
+                if depth == 0 {
+                    return Ok(i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    Err(error_at(src, start, "config object is never closed"))
+}
+
+/// Rewrites a JavaScript object literal as JSON, borrowing if it already is
+fn to_json<'a>(object: &'a str, offset: usize, src: &str) -> Result<Cow<'a, str>> {
+    let bytes = object.as_bytes();
+    let mut json = String::with_capacity(object.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(end) = skip_comment(object, i)? {
+            i = end;
+            continue;
+        }
+        match bytes[i] {
+            b'"' => {
+                let end = skip_string(object, i)?;
+                json.push_str(&object[i..end]);
+                i = end;
+            }
+            b'\'' | b'`' => {
+                let end = skip_string(object, i)?;
+                let raw = &object[i + 1..end - 1];
+                if bytes[i] == b'`' && raw.contains("${") {
+                    return Err(error_at(
+                        src,
+                        offset + i,
+                        "template string in config object",
+                    ));
+                }
+                json.push('"');
+                let mut chars = raw.chars();
+                while let Some(c) = chars.next() {
+                    match (c, chars.clone().next()) {
+                        ('\\', Some(q @ ('\'' | '`'))) => {
+                            json.push(q);
+                            chars.next();
+                        }
+                        ('\\', Some(next)) => {
+                            json.push('\\');
+                            json.push(next);
+                            chars.next();
+                        }
+                        ('"', _) => json.push_str("\\\""),
+                        ('\n', _) => json.push_str("\\n"),
+                        (c, _) => json.push(c),
+                    }
+                }
+                json.push('"');
+                i = end;
+            }
+            b',' => {
+                let next = skip_trivia(object, i + 1)?;
+                if !matches!(bytes.get(next), Some(b'}' | b']')) {
+                    json.push(',');
+                }
+                i += 1;
+            }
+            b'-' | b'0'..=b'9' => {
+                let end = i + 1 + bytes[i + 1..].iter().take_while(|&&c| is_number(c)).count();
+                json.push_str(&object[i..end]);
+                i = end;
+            }
+            c if is_ident(c) => {
+                let end = i + bytes[i..].iter().take_while(|&&c| is_ident(c)).count();
+                let word = &object[i..end];
+                let is_key = bytes.get(skip_trivia(object, end)?) == Some(&b':');
+                match word {
+                    _ if is_key => json.push_str(&format!("\"{word}\"")),
+                    "true" | "false" | "null" => json.push_str(word),
+                    "undefined" => json.push_str("null"),
+                    _ => {
+                        return Err(error_at(
+                            src,
+                            offset + i,
+                            &format!("unexpected `{word}` in config object"),
+                        ))
+                    }
+                }
+                i = end;
+            }
+            _ => {
+                let len = object[i..].chars().next().map_or(1, char::len_utf8);
+                json.push_str(&object[i..i + len]);
+                i += len;
+            }
+        }
+    }
+    Ok(match json == object {
+        true => Cow::Borrowed(object),
+        false => Cow::Owned(json),
+    })
+}
+
+/// Past the string literal opening at `i`
+fn skip_string(src: &str, i: usize) -> Result<usize> {
+    let bytes = src.as_bytes();
+    let quote = bytes[i];
+    let mut j = i + 1;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' => j += 2,
+            c if c == quote => return Ok(j + 1),
+            b'\n' if quote != b'`' => break,
+            _ => j += 1,
+        }
+    }
+    Err(error_at(src, i, "string is never closed"))
+}
+
+/// Past the comment starting at `i`, if there is one
+fn skip_comment(src: &str, i: usize) -> Result<Option<usize>> {
+    // Bytes, since `i` might be partway through a multibyte character
+    let rest = &src.as_bytes()[i..];
+    if rest.starts_with(b"//") {
+        let end = rest.iter().position(|&c| c == b'\n');
+        Ok(Some(end.map_or(src.len(), |end| i + end + 1)))
+    } else if let Some(body) = rest.strip_prefix(b"/*") {
+        match body.windows(2).position(|w| w == b"*/") {
+            Some(end) => Ok(Some(i + 2 + end + 2)),
+            None => Err(error_at(src, i, "comment is never closed")),
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+/// Past any whitespace and comments from `i`
+fn skip_trivia(src: &str, mut i: usize) -> Result<usize> {
+    let bytes = src.as_bytes();
+    loop {
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        match skip_comment(src, i)? {
+            Some(end) => i = end,
+            None => return Ok(i),
+        }
+    }
+}
+
+fn is_ident(c: u8) -> bool { c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$') }
+
+fn is_number(c: u8) -> bool { c.is_ascii_alphanumeric() || matches!(c, b'.' | b'+' | b'-') }
+
+fn error_at(src: &str, i: usize, message: &str) -> Error {
+    let before = &src[..i];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |nl| nl + 1) + 1;
+    Error::ConfigParse(format!(
+        "{message} at line {line} column {column} of config.js: {}",
+        snippet(&src.as_bytes()[i..])
+    ))
+}
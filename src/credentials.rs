@@ -0,0 +1,130 @@
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use ring::digest::{digest, SHA256};
+
+use crate::{
+    login::{self, Login},
+    Client, Error, Result, SecretKey, TokenClaims,
+};
+
+/// Somewhere to get a (fresh) API key from
+///
+/// The client asks once at startup if it wasn't given a key, and again
+/// whenever the current one gets rejected or expires. Every clone of the
+/// client shares whatever comes back
+pub trait CredentialProvider: fmt::Debug + Send + Sync + 'static {
+    /// Hands out a key. `client` is the client asking, for providers that
+    /// need to talk to Naviance themselves
+    fn key<'a>(&'a self, client: &'a Client) -> BoxFuture<'a, Result<SecretKey>>;
+}
+
+/// Always the same key, so nothing to refresh
+#[derive(Debug, Clone)]
+pub struct StaticKey(pub SecretKey);
+
+impl CredentialProvider for StaticKey {
+    fn key<'a>(&'a self, _client: &'a Client) -> BoxFuture<'a, Result<SecretKey>> {
+        futures::future::ready(Ok(self.0.clone())).boxed()
+    }
+}
+
+/// Reads the key from an environment variable every time, e.g. one a
+/// sidecar keeps up to date
+#[derive(Debug, Clone)]
+pub struct EnvKey(pub String);
+
+impl CredentialProvider for EnvKey {
+    fn key<'a>(&'a self, _client: &'a Client) -> BoxFuture<'a, Result<SecretKey>> {
+        let key = std::env::var(&self.0)
+            .map(SecretKey::from)
+            .map_err(|e| Error::Other(format!("Can't read key from ${}: {e}", self.0)));
+        futures::future::ready(key).boxed()
+    }
+}
+
+/// Reads the key from a file every time, ignoring surrounding whitespace
+#[derive(Debug, Clone)]
+pub struct FileKey(pub PathBuf);
+
+impl CredentialProvider for FileKey {
+    fn key<'a>(&'a self, _client: &'a Client) -> BoxFuture<'a, Result<SecretKey>> {
+        let path = self.0.clone();
+        async move {
+            let key = tokio::task::spawn_blocking(move || std::fs::read_to_string(path))
+                .await
+                .map_err(|e| Error::Other(format!("Failed to read key file: {e}")))??;
+            Ok(SecretKey::from(key.trim()))
+        }
+        .boxed()
+    }
+}
+
+/// Logs in again for every new key. An SSO redirect only works once, so
+/// that one fails with [`Error::LoginFailed`] when asked for a second key
+#[derive(Debug)]
+pub struct LoginKey {
+    login: Login,
+    spent: AtomicBool,
+}
+
+impl LoginKey {
+    pub fn new(login: Login) -> Self {
+        Self {
+            login,
+            spent: AtomicBool::new(false),
+        }
+    }
+}
+
+impl CredentialProvider for LoginKey {
+    fn key<'a>(&'a self, client: &'a Client) -> BoxFuture<'a, Result<SecretKey>> {
+        async move {
+            let single_use = matches!(self.login, Login::SsoRedirect(_));
+            if single_use && self.spent.swap(true, Ordering::Relaxed) {
+                return Err(Error::LoginFailed(
+                    "The SSO code has already been used - log in through SSO again".to_string(),
+                ));
+            }
+            let login_base = client.inner.login_base.clone().ok_or(Error::LoginFailed(
+                "No login URL known - the config was never fetched".to_string(),
+            ))?;
+            login::authenticate(client, login_base, &self.login).await
+        }
+        .boxed()
+    }
+}
+
+/// The key currently in use, and what it says about itself
+#[derive(Debug)]
+pub(crate) struct Credentials {
+    pub(crate) key:    SecretKey,
+    pub(crate) claims: Option<TokenClaims>,
+}
+
+impl Credentials {
+    pub(crate) fn new(key: SecretKey) -> Self {
+        Self {
+            claims: TokenClaims::decode(key.expose()),
+            key,
+        }
+    }
+
+    /// A short, filename-safe fingerprint of who the key belongs to. That's
+    /// the JWT `sub` if there is one, so it survives key refreshes, otherwise
+    /// the key itself
+    pub(crate) fn identity(&self) -> String {
+        let who = match self.claims.as_ref().and_then(|c| c.subject.as_deref()) {
+            Some(subject) => format!("sub:{subject}"),
+            None => format!("key:{}", self.key.expose()),
+        };
+        digest(&SHA256, who.as_bytes()).as_ref()[..8]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
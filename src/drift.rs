@@ -0,0 +1,182 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fmt,
+};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// The JSON type of a value, for drift reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum JsonType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Bool,
+            Value::Number(_) => Self::Number,
+            Value::String(_) => Self::String,
+            Value::Array(_) => Self::Array,
+            Value::Object(_) => Self::Object,
+        }
+    }
+}
+
+impl fmt::Display for JsonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Null => "null",
+            Self::Bool => "bool",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Array => "array",
+            Self::Object => "object",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How a single field differs from what our types know about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Drift {
+    /// The API sent a field we don't have
+    New { found: JsonType },
+    /// We have a field the API didn't send
+    Missing,
+    /// The API sent a field with a different type than ours
+    ChangedType {
+        expected: JsonType,
+        found:    JsonType,
+    },
+}
+
+/// Every drift seen so far, per endpoint and then per field path (with array
+/// indices collapsed, e.g. `data[].college.deadlines[].day`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DriftReport {
+    pub endpoints: BTreeMap<String, BTreeMap<String, Drift>>,
+}
+
+impl DriftReport {
+    pub fn is_empty(&self) -> bool { self.endpoints.values().all(BTreeMap::is_empty) }
+
+    /// Records drift for `endpoint`, returning the entries that weren't known
+    /// yet
+    pub(crate) fn merge(
+        &mut self,
+        endpoint: &str,
+        found: BTreeMap<String, Drift>,
+    ) -> Vec<(String, Drift)> {
+        let known = self.endpoints.entry(endpoint.to_string()).or_default();
+        let mut new = Vec::new();
+        for (path, drift) in found {
+            if let Entry::Vacant(entry) = known.entry(path.clone()) {
+                entry.insert(drift.clone());
+                new.push((path, drift));
+            }
+        }
+        new
+    }
+}
+
+impl fmt::Display for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (endpoint, fields) in self.endpoints.iter().filter(|(_, f)| !f.is_empty()) {
+            writeln!(f, "{endpoint}")?;
+            for (path, drift) in fields {
+                match drift {
+                    Drift::New { found } => writeln!(f, "\t+ {path} ({found})")?,
+                    Drift::Missing => writeln!(f, "\t- {path}")?,
+                    Drift::ChangedType { expected, found } => {
+                        writeln!(f, "\t~ {path} (expected {expected}, found {found})")?
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares what we decoded (serialized back to JSON) with what the API
+/// actually sent
+pub(crate) fn diff(typed: &Value, raw: &Value) -> BTreeMap<String, Drift> {
+    let mut found = BTreeMap::new();
+    diff_at(typed, raw, &mut String::new(), &mut found);
+    found
+}
+
+fn diff_at(typed: &Value, raw: &Value, path: &mut String, found: &mut BTreeMap<String, Drift>) {
+    match (typed, raw) {
+        (Value::Object(typed), Value::Object(raw)) => diff_objects(typed, raw, path, found),
+        (Value::Array(typed), Value::Array(raw)) => {
+            let len = path.len();
+            path.push_str("[]");
+            for (typed, raw) in typed.iter().zip(raw) {
+                diff_at(typed, raw, path, found);
+            }
+            path.truncate(len);
+        }
+        // Lenient fields turn "", 0, etc. into None, so a null on our side
+        // tells us nothing
+        (Value::Null, _) => {}
+        // `bool_from_int_opt`
+        (Value::Bool(_), Value::Number(_)) => {}
+        (typed, raw) => {
+            let (expected, found_type) = (JsonType::of(typed), JsonType::of(raw));
+            if expected != found_type {
+                found.entry(field_path(path)).or_insert(Drift::ChangedType {
+                    expected,
+                    found: found_type,
+                });
+            }
+        }
+    }
+}
+
+fn diff_objects(
+    typed: &Map<String, Value>,
+    raw: &Map<String, Value>,
+    path: &mut String,
+    found: &mut BTreeMap<String, Drift>,
+) {
+    let len = path.len();
+    for (key, raw_value) in raw {
+        push_key(path, key);
+        match typed.get(key) {
+            Some(typed_value) => diff_at(typed_value, raw_value, path, found),
+            None => {
+                found.entry(path.clone()).or_insert(Drift::New {
+                    found: JsonType::of(raw_value),
+                });
+            }
+        }
+        path.truncate(len);
+    }
+    for key in typed.keys().filter(|key| !raw.contains_key(*key)) {
+        push_key(path, key);
+        found.entry(path.clone()).or_insert(Drift::Missing);
+        path.truncate(len);
+    }
+}
+
+fn push_key(path: &mut String, key: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(key);
+}
+
+fn field_path(path: &str) -> String {
+    match path.is_empty() {
+        true => ".".to_string(),
+        false => path.to_string(),
+    }
+}
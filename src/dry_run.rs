@@ -0,0 +1,63 @@
+use std::fmt;
+
+use serde_json::Value;
+use url::Url;
+
+/// A write that would have been sent, see
+/// [`ClientBuilder::dry_run`](crate::ClientBuilder::dry_run)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRun {
+    pub method: http::Method,
+    pub url:    Url,
+    pub body:   Option<Value>,
+}
+
+impl DryRun {
+    pub(crate) fn new(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            url:    request.url().clone(),
+            body:   request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .and_then(|body| serde_json::from_slice(body).ok()),
+        }
+    }
+}
+
+impl fmt::Display for DryRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+        if let Some(body) = &self.body {
+            let body = serde_json::to_string_pretty(body).map_err(|_| fmt::Error)?;
+            write!(f, "\n{body}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What a write did, or would have done in dry-run mode
+#[derive(Debug, Clone, PartialEq)]
+pub enum Write<T> {
+    Done(T),
+    DryRun(DryRun),
+}
+
+impl<T> Write<T> {
+    /// The response, if the request was actually sent
+    pub fn done(self) -> Option<T> {
+        match self {
+            Self::Done(response) => Some(response),
+            Self::DryRun(_) => None,
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool { matches!(self, Self::DryRun(_)) }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Write<U> {
+        match self {
+            Self::Done(response) => Write::Done(f(response)),
+            Self::DryRun(dry_run) => Write::DryRun(dry_run),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::{path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use thiserror::Error;
+use url::Url;
+
+use crate::{retry::retry_after, util::snippet};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("URL error: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The API key was rejected - it's probably expired
+    #[error("Unauthorized: the API key was rejected")]
+    Unauthorized,
+    /// The API key is a JWT whose `exp` has already passed, so we didn't
+    /// bother asking
+    #[error("The API key expired at {expired_at}")]
+    KeyExpired { expired_at: DateTime<Utc> },
+    /// Logging in for a key didn't work out
+    #[error("Login failed: {0}")]
+    LoginFailed(String),
+    #[error("Not found: {endpoint}{}", id.as_ref().map(|id| format!(" ({id})")).unwrap_or_default())]
+    NotFound {
+        endpoint: String,
+        id:       Option<String>,
+    },
+    #[error("Rate limited{}", retry_after.map(|d| format!(", retry after {d:?}")).unwrap_or_default())]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("Server error {status}: {body_snippet}")]
+    Server {
+        status:       StatusCode,
+        body_snippet: String,
+        /// How long the server asked us to wait, if it did
+        retry_after:  Option<Duration>,
+    },
+    /// Any other non-success status
+    #[error("Unexpected status {status}: {body_snippet}")]
+    Status {
+        status:       StatusCode,
+        body_snippet: String,
+    },
+    /// `rewritten_config.js` couldn't be made sense of
+    #[error("Config parse error: {0}")]
+    ConfigParse(String),
+    /// A response body didn't match the type we expected
+    #[error(transparent)]
+    Decode(Box<DecodeError>),
+    /// Replaying a cassette that has nothing for this request
+    #[error("No recorded response for {method} {url}")]
+    NotRecorded { method: String, url: String },
+    /// A cache-only client has nothing cached for this request
+    #[error("Not cached: {url}")]
+    NotCached { endpoint: String, url: String },
+    /// The credential store couldn't be read or written
+    #[error("Credential store error: {0}")]
+    Store(String),
+    /// A local college list file couldn't be read
+    #[error("Target list error: {0}")]
+    TargetList(String),
+    #[error("Other error: {0}")]
+    Other(String),
+}
+
+/// The details of an [`Error::Decode`], boxed so every other `Result`
+/// doesn't have to carry them around
+#[derive(Debug, Error)]
+#[error(
+    "Failed to decode {endpoint} at `{json_path}`: {source}{}{}",
+    value_snippet.as_ref().map(|v| format!(" (got: {v})")).unwrap_or_default(),
+    dump.as_ref().map(|d| format!(" (body dumped to {})", d.display())).unwrap_or_default()
+)]
+pub struct DecodeError {
+    pub endpoint:      String,
+    /// Where in the body it went wrong, e.g.
+    /// `data[3].college.deadlines[0].day`
+    pub json_path:     String,
+    /// What the type wanted to see there, if serde told us
+    pub expected:      Option<String>,
+    /// The value that was actually there, if the body is valid JSON
+    pub value_snippet: Option<String>,
+    pub body_snippet:  String,
+    /// Where the full body was written to, if a debug dump directory is set
+    pub dump:          Option<PathBuf>,
+    #[source]
+    pub source:        serde_json::Error,
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self { Error::Decode(Box::new(e)) }
+}
+
+impl Error {
+    /// Turns a non-success response from `endpoint` under `base` into the
+    /// matching error, passing successful responses through untouched
+    pub(crate) async fn check_response(
+        base: &Url,
+        endpoint: &str,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        match status {
+            StatusCode::UNAUTHORIZED => Err(Error::Unauthorized),
+            StatusCode::NOT_FOUND => {
+                let id = response
+                    .url()
+                    .path()
+                    .strip_prefix(base.path().trim_end_matches('/'))
+                    .and_then(|path| path.strip_prefix(endpoint))
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .map(|rest| rest.trim_end_matches('/'))
+                    .filter(|rest| !rest.is_empty())
+                    .map(str::to_string);
+                Err(Error::NotFound {
+                    endpoint: endpoint.to_string(),
+                    id,
+                })
+            }
+            StatusCode::TOO_MANY_REQUESTS => Err(Error::RateLimited {
+                retry_after: retry_after(&response),
+            }),
+            _ => {
+                let retry_after = retry_after(&response);
+                let body_snippet = snippet(&response.bytes().await.unwrap_or_default());
+                match status.is_server_error() {
+                    true => Err(Error::Server {
+                        status,
+                        body_snippet,
+                        retry_after,
+                    }),
+                    false => Err(Error::Status {
+                        status,
+                        body_snippet,
+                    }),
+                }
+            }
+        }
+    }
+}
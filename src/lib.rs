@@ -1,34 +1,68 @@
-use std::sync::Arc;
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock, RwLockReadGuard,
+    },
+    time::Duration,
+};
 
+mod builder;
+pub mod cache;
+pub mod cassette;
+mod coalesce;
+mod config_js;
+pub mod credentials;
+pub mod drift;
+mod dry_run;
+mod error;
+pub mod login;
+mod paginate;
+mod ratelimit;
+mod retry;
+mod secret;
+pub mod store;
+pub mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod token;
 pub mod types;
 pub mod util;
 
+pub use builder::ClientBuilder;
+use bytes::Bytes;
+use cache::{CacheMode, ResponseCache};
+use cassette::Tape;
+use chrono::{DateTime, Utc};
+use coalesce::InFlight;
+use credentials::{CredentialProvider, Credentials};
+use drift::{Drift, DriftReport};
+pub use dry_run::{DryRun, Write};
+pub use error::{DecodeError, Error, Result};
+use http::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    HeaderValue, StatusCode,
+};
 use lazy_static::lazy_static;
+pub use paginate::Paginator;
+pub use ratelimit::RateLimit;
+use ratelimit::RateLimiter;
+pub use retry::RetryPolicy;
+pub use secret::SecretKey;
+use serde::de;
 use serde_json::Value;
-use thiserror::Error;
-use tracing::debug;
+pub use token::TokenClaims;
+use tracing::{debug, info, warn};
 use types::{
-    ApplicationStatistics, AuthEndpoint, College, ScattergramSource, ScattergramSources,
-    SchoolsImThinkingAbout,
+    AddSchoolImThinkingAbout, Application, ApplicationStatistics, AuthEndpoint, College,
+    CollegeQuery, CollegeSearch, CollegesImApplyingTo, Config, NewSchool, Paged, PagedEndpoint,
+    RemoveSchoolImThinkingAbout, ScattergramSources, School, SchoolUpdate, SchoolsImThinkingAbout,
+    ServiceKind, UpdateSchoolImThinkingAbout,
 };
 use url::Url;
 use uuid::Uuid;
 
-use crate::types::{Config, Endpoint, PublicEndpoint};
-
-pub type Result<T> = std::result::Result<T, Error>;
-
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
-    #[error("JSON error: {0}")]
-    Json(#[from] serde_json::Error),
-    #[error("URL error: {0}")]
-    Url(#[from] url::ParseError),
-    #[error("Other error: {0}")]
-    Other(String),
-}
+use crate::types::{Endpoint, PublicEndpoint};
 
 #[derive(Debug, Clone)]
 pub struct Client {
@@ -39,8 +73,28 @@ pub struct Client {
 
 #[derive(Debug)]
 struct ClientRef {
-    key:      String,
-    api_base: Url,
+    credentials:    RwLock<Credentials>,
+    /// Where new keys come from, if anywhere
+    provider:       Option<Box<dyn CredentialProvider>>,
+    /// Held while getting a new key so only one clone asks at a time
+    refreshing:     tokio::sync::Mutex<()>,
+    login_base:     Option<Url>,
+    /// `rewritten_config.js`, unless the API base was given up front
+    config:         Option<Config>,
+    api_base:       Url,
+    retry:          RetryPolicy,
+    limiter:        Option<RateLimiter>,
+    dump_dir:       Option<PathBuf>,
+    drift:          Option<Mutex<DriftReport>>,
+    strict:         bool,
+    /// Show writes instead of sending them
+    dry_run:        bool,
+    tape:           Option<Tape>,
+    cache:          Option<ResponseCache>,
+    inflight:       Option<InFlight>,
+    /// How long before the key expires to start warning
+    expiry_warning: Duration,
+    expiry_warned:  AtomicBool,
 }
 
 lazy_static! {
@@ -48,38 +102,66 @@ lazy_static! {
 }
 impl Client {
     /// Create a new client from an API key with sensible HTTP client settings
-    pub async fn new(key: String) -> Result<Self> {
-        let http_client = reqwest::Client::builder()
-            .https_only(true)
-            .use_rustls_tls()
-            .brotli(true)
-            .gzip(true)
-            .zstd(true)
-            .deflate(true)
-            .build()?;
-        Self::new_with_client(key, http_client).await
+    pub async fn new(key: impl Into<SecretKey>) -> Result<Self> {
+        Self::builder().key(key).build().await
     }
 
     /// Create a new client from an API key with a custom HTTP client
     ///
     /// Warning: You should probably use an HTTP client with HTTPS-only
-    pub async fn new_with_client(key: String, http_client: reqwest::Client) -> Result<Self> {
-        let student_base = STUDENT_BASE.clone();
-        let config_resp = Config::request(student_base, &http_client).await?;
-        let config = Config::extract(config_resp).await?;
-        let api_base_raw = config.api_host.ok_or(Error::Other(
-            "No API host found in rewritten_config.js".to_string(),
-        ))?;
-        let api_base = Url::parse(&api_base_raw)?;
-        let inner = Arc::new(ClientRef { key, api_base });
-        Ok(Self { http_client, inner })
-    }
-
-    /// Fetches all the schools on your personal list
+    pub async fn new_with_client(
+        key: impl Into<SecretKey>,
+        http_client: reqwest::Client,
+    ) -> Result<Self> {
+        Self::builder()
+            .key(key)
+            .http_client(http_client)
+            .build()
+            .await
+    }
+
+    /// Configure a client with a custom student/API base, timeouts, etc.
+    pub fn builder() -> ClientBuilder { ClientBuilder::new() }
+
+    /// Fetches the first page of schools on your personal list
     pub async fn get_schools_im_thinking_about(&self) -> Result<types::SchoolsImThinkingAbout> {
         self.fetch_auth::<SchoolsImThinkingAbout>().await
     }
 
+    /// Fetches all the schools on your personal list, across every page
+    pub async fn get_all_schools_im_thinking_about(&self) -> Result<Vec<types::School>> {
+        self.paginate::<SchoolsImThinkingAbout>()
+            .collect_all()
+            .await
+    }
+
+    /// Fetches the first page of colleges matching `query`
+    pub async fn search_colleges(&self, query: &CollegeQuery) -> Result<Paged<College>> {
+        self.fetch_auth_with::<CollegeSearch>(query).await
+    }
+
+    /// Walks every college matching `query`, e.g.
+    /// `client.search_colleges_paginated(query).page_size(50).stream()`
+    pub fn search_colleges_paginated(&self, query: CollegeQuery) -> Paginator<CollegeSearch> {
+        self.paginate_with::<CollegeSearch>(query)
+    }
+
+    /// Fetches the first page of colleges you're applying to
+    pub async fn get_colleges_im_applying_to(&self) -> Result<CollegesImApplyingTo> {
+        self.fetch_auth::<CollegesImApplyingTo>().await
+    }
+
+    /// Fetches every college you're applying to, across every page
+    pub async fn get_all_colleges_im_applying_to(&self) -> Result<Vec<Application>> {
+        self.paginate::<CollegesImApplyingTo>().collect_all().await
+    }
+
+    /// Fetches one application in full, including recommendation letters, by
+    /// its [`Application::id`]
+    pub async fn get_application(&self, id: u32) -> Result<Application> {
+        self.fetch_auth_with::<Application>(&id).await
+    }
+
     /// Fetches all the possible scattergram sources for your school
     pub async fn get_scattergram_sources(&self) -> Result<ScattergramSources> {
         self.fetch_auth::<ScattergramSources>().await
@@ -87,36 +169,423 @@ impl Client {
 
     /// Fetches a specific college's information given its UUID
     pub async fn get_college_info_by_uuid(&self, uuid: &Uuid) -> Result<College> {
-        let base = self.api_base().clone();
-        let resp = College::request(base, &self.http_client, self.key(), uuid).await?;
-        College::extract(resp).await
+        self.fetch_auth_with::<College>(uuid).await
     }
 
     pub async fn get_application_stats_by_uuid(
         &self,
         uuid: &Uuid,
     ) -> Result<ApplicationStatistics> {
-        let base = self.api_base().clone();
-        let resp =
-            ApplicationStatistics::request(base, &self.http_client, self.key(), uuid).await?;
-        ApplicationStatistics::extract(resp).await
+        self.fetch_auth_with::<ApplicationStatistics>(uuid).await
+    }
+
+    /// Adds a college to your personal list, e.g.
+    /// `client.add_school_im_thinking_about(&NewSchool::new(uuid))`
+    pub async fn add_school_im_thinking_about(&self, school: &NewSchool) -> Result<Write<School>> {
+        self.write_auth_with::<AddSchoolImThinkingAbout>(school)
+            .await
+    }
+
+    /// Changes the interest level and/or expected outcome of a school on
+    /// your personal list
+    pub async fn update_school_im_thinking_about(
+        &self,
+        update: &SchoolUpdate,
+    ) -> Result<Write<School>> {
+        self.write_auth_with::<UpdateSchoolImThinkingAbout>(update)
+            .await
+    }
+
+    /// Takes a school off your personal list, by its [`School::id`]
+    pub async fn remove_school_im_thinking_about(&self, id: u32) -> Result<Write<()>> {
+        self.write_auth_with::<RemoveSchoolImThinkingAbout>(&id)
+            .await
     }
 
     /// Fetches any static public endpoint - use with caution
-    pub async fn fetch<T: PublicEndpoint>(&self) -> Result<T::Response> {
+    pub async fn fetch<T: PublicEndpoint<Params = ()>>(&self) -> Result<T::Response> {
+        self.fetch_with::<T>(&()).await
+    }
+
+    /// Fetches any public endpoint with parameters - use with caution
+    pub async fn fetch_with<T: PublicEndpoint>(&self, params: &T::Params) -> Result<T::Response> {
         let base = self.api_base().clone();
-        let resp = T::request(base, &self.http_client).await?;
-        T::extract(resp).await
+        let req = T::request(base, &self.http_client, params)?;
+        self.send::<T>(req).await
     }
 
     /// Fetches any static authenticated endpoint - use with caution
-    pub async fn fetch_auth<T: AuthEndpoint>(&self) -> Result<T::Response> {
-        let base = self.api_base().clone();
-        let resp = T::request(base, &self.http_client, self.key()).await?;
-        T::extract(resp).await
+    pub async fn fetch_auth<T: AuthEndpoint<Params = ()>>(&self) -> Result<T::Response> {
+        self.fetch_auth_with::<T>(&()).await
     }
 
-    pub fn key(&self) -> &str { &self.inner.key }
+    /// Fetches any authenticated endpoint with parameters, e.g.
+    /// `client.fetch_auth_with::<College>(&uuid)` - use with caution
+    pub async fn fetch_auth_with<T: AuthEndpoint>(
+        &self,
+        params: &T::Params,
+    ) -> Result<T::Response> {
+        let base = self.api_base();
+        self.send_auth::<T>(|key| T::request(base.clone(), &self.http_client, key, params))
+            .await
+    }
+
+    /// Sends a request that changes something, or just returns what it would
+    /// have sent if the client is in [dry-run](ClientBuilder::dry_run) mode
+    pub async fn write_auth_with<T: AuthEndpoint>(
+        &self,
+        params: &T::Params,
+    ) -> Result<Write<T::Response>> {
+        if self.inner.dry_run {
+            let req = T::build(self.api_base().clone(), &self.http_client, params)?.build()?;
+            let dry_run = DryRun::new(&req);
+            info!("Dry run, not sending: {} {}", dry_run.method, dry_run.url);
+            return Ok(Write::DryRun(dry_run));
+        }
+        Ok(Write::Done(self.fetch_auth_with::<T>(params).await?))
+    }
+
+    /// Fetches a single page of a paged endpoint. Pages start at 1
+    pub async fn fetch_page<T: PagedEndpoint>(
+        &self,
+        params: &T::Params,
+        page: u32,
+        limit: Option<u32>,
+    ) -> Result<Paged<T::Item>> {
+        let base = self.api_base();
+        self.send_auth::<T>(|key| {
+            let mut req = T::request(base.clone(), &self.http_client, key, params)?;
+            let mut query = req.url_mut().query_pairs_mut();
+            query.append_pair("page", &page.to_string());
+            if let Some(limit) = limit {
+                query.append_pair("limit", &limit.to_string());
+            }
+            drop(query);
+            Ok(req)
+        })
+        .await
+    }
+
+    /// Sends an authenticated request for `T`, built by `request` with the
+    /// current key. If the key has expired or gets rejected, a new one comes
+    /// from the [`CredentialProvider`] and the request is tried once more
+    async fn send_auth<T: Endpoint>(
+        &self,
+        request: impl Fn(&str) -> Result<reqwest::Request>,
+    ) -> Result<T::Response> {
+        let refreshable = self.inner.provider.is_some();
+        let key = match self.check_key() {
+            Err(Error::KeyExpired { .. }) if refreshable => self.refresh_key(&self.key()).await?,
+            checked => checked.map(|_| self.key())?,
+        };
+        match self.send::<T>(request(key.expose())?).await {
+            Err(Error::Unauthorized) if refreshable => {
+                let fresh = self.refresh_key(&key).await?;
+                if fresh == key {
+                    return Err(Error::Unauthorized);
+                }
+                self.send::<T>(request(fresh.expose())?).await
+            }
+            result => result,
+        }
+    }
+
+    /// Swaps `stale` for a new key from the [`CredentialProvider`], unless
+    /// another clone already has
+    async fn refresh_key(&self, stale: &SecretKey) -> Result<SecretKey> {
+        let Some(provider) = &self.inner.provider else {
+            return Ok(self.key());
+        };
+        let _refreshing = self.inner.refreshing.lock().await;
+        let current = self.key();
+        if current != *stale {
+            return Ok(current);
+        }
+        let fresh = provider.key(self).await?;
+        debug!("Got a new API key");
+        *self
+            .inner
+            .credentials
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Credentials::new(fresh.clone());
+        self.inner.expiry_warned.store(false, Ordering::Relaxed);
+        Ok(fresh)
+    }
+
+    /// Walks every page of a static paged endpoint, e.g.
+    /// `client.paginate::<SchoolsImThinkingAbout>().collect_all()`
+    pub fn paginate<T: PagedEndpoint<Params = ()>>(&self) -> Paginator<T> {
+        self.paginate_with::<T>(())
+    }
+
+    /// Walks every page of a paged endpoint with parameters
+    pub fn paginate_with<T: PagedEndpoint>(&self, params: T::Params) -> Paginator<T> {
+        Paginator::new(self.clone(), params)
+    }
+
+    /// Sends a request for `T` and decodes the response
+    async fn send<T: Endpoint>(&self, request: reqwest::Request) -> Result<T::Response> {
+        let cached = self
+            .cache_for::<T>(&request)
+            .map(|(cache, _)| (cache, cache.path_for(&request, &self.identity())));
+        let body = self.coalesced_body::<T>(request).await?;
+        let mut result = util::with_strictness(self.inner.strict, || T::extract(&body));
+        if let Ok(response) = &result {
+            if self.inner.strict || self.inner.drift.is_some() {
+                result = self.check_drift::<T>(response, &body).and(result);
+            }
+        }
+        if let (Err(Error::Decode(e)), Some(dir)) = (&mut result, &self.inner.dump_dir) {
+            e.dump = util::dump_body(dir, T::PATH, &body);
+        }
+        let undecodable = matches!(result, Err(Error::Decode(_)));
+        if let (true, Some((cache, path))) = (undecodable, cached) {
+            // Don't keep serving something we can't read
+            cache.remove(path).await;
+        }
+        result
+    }
+
+    /// Like [`Client::fetch_body`], but identical `GET`s that are already in
+    /// flight share a single request
+    async fn coalesced_body<T: Endpoint>(&self, request: reqwest::Request) -> Result<Bytes> {
+        match &self.inner.inflight {
+            Some(inflight) if request.method() == http::Method::GET => {
+                let key = format!("{} {}", request.method(), request.url());
+                inflight.run(key, || self.fetch_body::<T>(request)).await
+            }
+            _ => self.fetch_body::<T>(request).await,
+        }
+    }
+
+    /// The cache and TTL to use for `request`, if `T` is cacheable
+    fn cache_for<T: Endpoint>(
+        &self,
+        request: &reqwest::Request,
+    ) -> Option<(&ResponseCache, Duration)> {
+        let cache = self.inner.cache.as_ref()?;
+        match request.method() == http::Method::GET {
+            true => Some((cache, cache.ttl_for::<T>()?)),
+            false => None,
+        }
+    }
+
+    /// Gets the response body for `T`, from the cache if possible
+    async fn fetch_body<T: Endpoint>(&self, mut request: reqwest::Request) -> Result<Bytes> {
+        let Some((cache, ttl)) = self.cache_for::<T>(&request) else {
+            return Ok(self.execute(T::PATH, request).await?.bytes().await?);
+        };
+        let path = cache.path_for(&request, &self.identity());
+        let entry = match cache.get_mode() {
+            CacheMode::Refresh => None,
+            _ => cache.load(path.clone()).await,
+        };
+        match (cache.get_mode(), &entry) {
+            (CacheMode::CacheOnly, Some(entry)) => return Ok(entry.body()),
+            (CacheMode::CacheOnly, None) => {
+                return Err(Error::NotCached {
+                    endpoint: T::PATH.to_string(),
+                    url:      request.url().to_string(),
+                })
+            }
+            (CacheMode::Normal, Some(entry)) if entry.is_fresh(ttl) => return Ok(entry.body()),
+            _ => {}
+        }
+        // Stale, so ask the server whether it's still good
+        if let Some(entry) = &entry {
+            let validators = [
+                (IF_NONE_MATCH, &entry.etag),
+                (IF_MODIFIED_SINCE, &entry.last_modified),
+            ];
+            for (name, value) in validators {
+                if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                    request.headers_mut().insert(name, value);
+                }
+            }
+        }
+        let url = request.url().to_string();
+        let response = self.dispatch(request).await?;
+        if let (StatusCode::NOT_MODIFIED, Some(mut entry)) = (response.status(), entry) {
+            entry.stored_at = Utc::now();
+            cache.store(path, &entry).await;
+            return Ok(entry.body());
+        }
+        let response = Error::check_response(self.api_base(), T::PATH, response).await?;
+        let header = |name| {
+            let value = response.headers().get(name)?;
+            Some(value.to_str().ok()?.to_string())
+        };
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        let body = response.bytes().await?;
+        let entry = cache::Entry {
+            endpoint: Some(T::PATH.to_string()),
+            url,
+            stored_at: Utc::now(),
+            etag,
+            last_modified,
+            body: String::from_utf8_lossy(&body).to_string(),
+        };
+        cache.store(path, &entry).await;
+        Ok(body)
+    }
+
+    /// Writes what's been [recorded](ClientBuilder::record) so far to the
+    /// cassette. Nothing is written otherwise, so call this before the client
+    /// goes away (and as often as you like before that)
+    pub async fn save_cassette(&self) -> Result<()> {
+        match &self.inner.tape {
+            Some(tape) => tape.flush().await,
+            None => Ok(()),
+        }
+    }
+
+    /// The response cache, if there is one
+    pub fn cache(&self) -> Option<&ResponseCache> { self.inner.cache.as_ref() }
+
+    /// Compares a decoded response against the raw body, remembering any
+    /// fields that don't line up and failing on unknown ones in strict mode
+    fn check_drift<T: Endpoint>(&self, response: &T::Response, body: &[u8]) -> Result<()> {
+        let raw = T::json(body)
+            .ok()
+            .and_then(|json| serde_json::from_slice::<Value>(&json).ok());
+        let (Some(raw), Ok(typed)) = (raw, serde_json::to_value(response)) else {
+            return Ok(());
+        };
+        let found = drift::diff(&typed, &raw);
+        if found.is_empty() {
+            return Ok(());
+        }
+        let unknown = found
+            .iter()
+            .find(|(_, drift)| matches!(drift, Drift::New { .. }))
+            .map(|(path, _)| path.clone());
+        if let Some(drift) = &self.inner.drift {
+            let mut report = util::lock(drift);
+            for (path, drift) in report.merge(T::PATH, found) {
+                warn!("Schema drift in {}: {path}: {drift:?}", T::PATH);
+            }
+        }
+        match (self.inner.strict, unknown) {
+            (true, Some(path)) => Err(DecodeError {
+                endpoint:      T::PATH.to_string(),
+                value_snippet: None,
+                expected:      None,
+                body_snippet:  util::snippet(body),
+                dump:          None,
+                source:        de::Error::custom(format!("unknown field `{path}`")),
+                json_path:     path,
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Everything drift detection has found so far, if it's enabled
+    pub fn drift_report(&self) -> Option<DriftReport> {
+        let drift = self.inner.drift.as_ref()?;
+        Some(util::lock(drift).clone())
+    }
+
+    /// Sends a request to `endpoint`, failing on any non-success status
+    async fn execute(
+        &self,
+        endpoint: &str,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        let response = self.dispatch(request).await?;
+        Error::check_response(self.api_base(), endpoint, response).await
+    }
+
+    /// Sends a request, retrying it according to the client's
+    /// [`RetryPolicy`] and [`RateLimit`] - or answers it from the cassette
+    /// when replaying
+    async fn dispatch(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        let response = match &self.inner.tape {
+            Some(tape) if tape.is_replay() => tape.play(self.key().expose(), &request)?,
+            tape => {
+                let key = self.key();
+                let recorded = tape.as_ref().map(|tape| {
+                    let method = request.method().to_string();
+                    (tape, method, cassette::request_body(&request, key.expose()))
+                });
+                let limiter = self.inner.limiter.as_ref();
+                let response =
+                    retry::execute(&self.http_client, &self.inner.retry, limiter, request).await?;
+                match recorded {
+                    Some((tape, method, body)) => {
+                        tape.capture(key.expose(), method, body, response).await?
+                    }
+                    None => response,
+                }
+            }
+        };
+        Ok(response)
+    }
+
+    /// Fails if the key has already expired, and warns (once) if it's about
+    /// to. Keys that aren't JWTs always pass
+    fn check_key(&self) -> Result<()> {
+        let Some(expires_at) = self.key_expires_at() else {
+            return Ok(());
+        };
+        let replaying = self.inner.tape.as_ref().is_some_and(Tape::is_replay);
+        let left = expires_at.signed_duration_since(Utc::now());
+        if left <= chrono::Duration::zero() && !replaying {
+            return Err(Error::KeyExpired {
+                expired_at: expires_at,
+            });
+        }
+        let soon = left
+            .to_std()
+            .map_or(true, |left| left < self.inner.expiry_warning);
+        if soon && !self.inner.expiry_warned.swap(true, Ordering::Relaxed) {
+            warn!("The API key expires at {expires_at} - grab a new one soon");
+        }
+        Ok(())
+    }
+
+    fn credentials(&self) -> RwLockReadGuard<'_, Credentials> {
+        self.inner
+            .credentials
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The API key in use right now - it changes whenever a
+    /// [`CredentialProvider`] hands out a new one
+    pub fn key(&self) -> SecretKey { self.credentials().key.clone() }
+
+    /// Who the current key belongs to, for keeping their cache entries apart
+    fn identity(&self) -> String { self.credentials().identity() }
+
+    /// The API key's claims, if it's a JWT
+    pub fn token_claims(&self) -> Option<TokenClaims> { self.credentials().claims.clone() }
+
+    /// When the API key expires, if it's a JWT that says so
+    pub fn key_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.credentials().claims.as_ref()?.expires_at
+    }
+
+    /// Who the API key belongs to, if it's a JWT that says so
+    pub fn key_subject(&self) -> Option<String> {
+        self.credentials().claims.as_ref()?.subject.clone()
+    }
 
     pub fn api_base(&self) -> &Url { &self.inner.api_base }
+
+    /// Whether writes are only being shown, see [`ClientBuilder::dry_run`]
+    pub fn is_dry_run(&self) -> bool { self.inner.dry_run }
+
+    /// The discovered `rewritten_config.js`. `None` if
+    /// [`ClientBuilder::api_base`] skipped fetching it
+    pub fn config(&self) -> Option<&Config> { self.inner.config.as_ref() }
+
+    /// Where another Naviance service lives, for building clients for it.
+    /// [`ServiceKind::Api`] is always known
+    pub fn service_url(&self, kind: ServiceKind) -> Option<Url> {
+        match kind {
+            ServiceKind::Api => Some(self.inner.api_base.clone()),
+            kind => self.config()?.service_url(kind).cloned(),
+        }
+    }
 }
@@ -0,0 +1,119 @@
+use url::Url;
+
+use crate::{
+    retry,
+    types::{
+        Config, LoginToken, PasswordLogin, PasswordLoginParams, PublicEndpoint, ServiceKind,
+        SsoLogin, SsoLoginParams,
+    },
+    util, Client, Error, Result, SecretKey,
+};
+
+/// How to get an API key without digging it out of devtools. Set with
+/// [`ClientBuilder::login`](crate::ClientBuilder::login)
+#[derive(Debug, Clone)]
+pub enum Login {
+    Password(PasswordLoginParams),
+    /// Where the browser ended up after an SSO login started at
+    /// [`ClientBuilder::sso_url`](crate::ClientBuilder::sso_url)
+    SsoRedirect(Url),
+}
+
+impl Login {
+    pub fn password(username: impl Into<String>, password: impl Into<SecretKey>) -> Self {
+        Self::Password(PasswordLoginParams {
+            username: username.into(),
+            password: password.into(),
+            school:   None,
+        })
+    }
+
+    /// Same as [`Login::password`], for usernames that are only unique
+    /// within a school
+    pub fn school_password(
+        school: impl Into<String>,
+        username: impl Into<String>,
+        password: impl Into<SecretKey>,
+    ) -> Self {
+        Self::Password(PasswordLoginParams {
+            username: username.into(),
+            password: password.into(),
+            school:   Some(school.into()),
+        })
+    }
+}
+
+/// The login service, from `UNIFIED_USER_LOGIN_URL`
+pub(crate) fn login_base(config: &Config) -> Result<Url> {
+    config
+        .service_url(ServiceKind::Login)
+        .cloned()
+        .ok_or(Error::ConfigParse(
+            "No login URL found in rewritten_config.js".to_string(),
+        ))
+}
+
+/// Where to send the browser to start an SSO login for `school`
+pub(crate) fn sso_url(login_base: &Url, school: &str, redirect_uri: &Url) -> Result<Url> {
+    let mut url = util::join(login_base, "sso")?;
+    url.query_pairs_mut()
+        .append_pair("hsid", school)
+        .append_pair("redirect_uri", redirect_uri.as_str());
+    Ok(url)
+}
+
+/// Runs `login` against the login service at `login_base`
+pub(crate) async fn authenticate(
+    client: &Client,
+    login_base: Url,
+    login: &Login,
+) -> Result<SecretKey> {
+    match login {
+        Login::Password(params) => exchange::<PasswordLogin>(client, login_base, params).await,
+        Login::SsoRedirect(redirect) => {
+            let mut code = None;
+            let fragment = redirect
+                .fragment()
+                .map(|f| url::form_urlencoded::parse(f.as_bytes()));
+            for (name, value) in redirect.query_pairs().chain(fragment.into_iter().flatten()) {
+                match name.as_ref() {
+                    // Some providers hand the token over directly
+                    "token" | "access_token" => return Ok(value.as_ref().into()),
+                    "code" => code = Some(SecretKey::from(value.as_ref())),
+                    "error_description" | "error" => {
+                        return Err(Error::LoginFailed(format!("SSO provider said: {value}")))
+                    }
+                    _ => {}
+                }
+            }
+            let code = code.ok_or(Error::LoginFailed(
+                "No token or code in the SSO redirect URL".to_string(),
+            ))?;
+            let mut redirect_uri = redirect.clone();
+            redirect_uri.set_query(None);
+            redirect_uri.set_fragment(None);
+            let params = SsoLoginParams { code, redirect_uri };
+            exchange::<SsoLogin>(client, login_base, &params).await
+        }
+    }
+}
+
+async fn exchange<T: PublicEndpoint<Response = LoginToken>>(
+    client: &Client,
+    login_base: Url,
+    params: &T::Params,
+) -> Result<SecretKey> {
+    let request = T::request(login_base.clone(), &client.http_client, params)?;
+    // Straight to the network - credentials have no business in cassettes or
+    // caches
+    let limiter = client.inner.limiter.as_ref();
+    let response =
+        retry::execute(&client.http_client, &client.inner.retry, limiter, request).await?;
+    let response = match Error::check_response(&login_base, T::PATH, response).await {
+        Err(Error::Unauthorized) => {
+            return Err(Error::LoginFailed("Credentials were rejected".to_string()))
+        }
+        response => response?,
+    };
+    Ok(T::extract(&response.bytes().await?)?.token)
+}
@@ -4,7 +4,9 @@ extern crate tracing;
 
 use std::{
     collections::{HashMap, HashSet},
+    io::IsTerminal,
     ops::{Add, AddAssign},
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc, Mutex,
@@ -12,47 +14,242 @@ use std::{
 };
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use console::Term;
 use dotenvy::dotenv;
 use futures::{stream, StreamExt, TryStreamExt};
-use naviance::{types::*, util::sat_to_act, Client};
+use naviance::{
+    cache::{CacheMode, ResponseCache},
+    credentials::FileKey,
+    login::Login,
+    store::{CredentialStore, Profile, StoredLogin, StudentInfo},
+    sync::{SyncPlan, TargetList},
+    types::*,
+    util::sat_to_act,
+    Client, RateLimit, SecretKey, Write,
+};
 use reqwest::ClientBuilder;
 use tracing_log::LogTracer;
 use tracing_subscriber::{fmt, EnvFilter};
+use url::Url;
+use uuid::Uuid;
 
 #[derive(Parser, Debug)]
 struct Opts {
     #[clap(short, long, env)]
-    /// if you don't know how to find this, you probably shouldn't be using it
-    pub key: String,
+    /// bearer token from devtools - or log in with --username, --sso or
+    /// --profile instead
+    pub key:           Option<SecretKey>,
+    #[clap(long, env)]
+    /// read the key from this file, rereading it whenever the key is rejected
+    pub key_file:      Option<PathBuf>,
+    #[clap(short, long, env, requires = "password")]
+    /// log in with this username instead of passing a key
+    pub username:      Option<String>,
+    #[clap(long, env, hide_env_values = true)]
+    pub password:      Option<SecretKey>,
+    #[clap(long, env)]
+    /// your school's Naviance ID, for SSO or usernames that aren't unique
+    pub school:        Option<String>,
+    #[clap(long, requires = "school", conflicts_with = "username")]
+    /// log in through your school's SSO provider in a browser
+    pub sso:           bool,
+    #[clap(short, long, env)]
+    /// use a saved profile (defaults to the one picked with `profile use`)
+    pub profile:       Option<String>,
+    #[clap(long, env)]
+    /// where profiles are kept (defaults to naviance/credentials.enc in your
+    /// config dir)
+    pub store:         Option<PathBuf>,
+    #[clap(long, env = "STORE_PASSPHRASE", hide_env_values = true)]
+    /// passphrase for the profile store - asked for if not set
+    pub passphrase:    Option<SecretKey>,
+    #[clap(long, env, default_value_t = 5.)]
+    /// max requests per second to the API (<= 0 for unlimited)
+    pub rps:           f64,
+    #[clap(long, env, default_value_t = 8)]
+    /// max requests waiting on a response at once
+    pub max_in_flight: usize,
+    #[clap(long, conflicts_with = "replay")]
+    /// record the whole session to this cassette file
+    pub record:        Option<PathBuf>,
+    #[clap(long)]
+    /// replay a recorded cassette instead of hitting the API
+    pub replay:        Option<PathBuf>,
+    #[clap(long, env)]
+    /// keep college profiles and application stats here between runs
+    pub cache_dir:     Option<PathBuf>,
+    #[clap(long, requires = "cache_dir", conflicts_with = "refresh")]
+    /// only use cached college profiles and application stats
+    pub offline:       bool,
+    #[clap(long, requires = "cache_dir")]
+    /// refetch everything that's cached
+    pub refresh:       bool,
+    #[clap(long)]
+    /// print changes instead of making them
+    pub dry_run:       bool,
+    #[command(subcommand)]
+    pub command:       Option<Command>,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    dotenv().ok(); // Contains KEY
-    let opts = Opts::parse();
-    LogTracer::init().expect("Failed to initialize LogTracer");
-    let subscriber = fmt::Subscriber::builder()
-        .with_env_filter(EnvFilter::from_default_env())
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed to set global tracing subscriber");
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// manage saved profiles
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+    /// change the list of colleges you're thinking about
+    #[command(subcommand)]
+    Schools(SchoolsCommand),
+    /// make the list match a .toml or .csv file of college UUIDs
+    Sync {
+        file: PathBuf,
+        #[clap(short, long)]
+        /// don't ask before making changes
+        yes:  bool,
+    },
+}
 
-    // HTTP client
-    let c = ClientBuilder::new()
-        .use_rustls_tls()
-        .https_only(true)
-        .brotli(true)
-        .gzip(true)
-        .zstd(true)
-        .deflate(true)
-        .build()?;
+#[derive(Subcommand, Debug)]
+enum SchoolsCommand {
+    /// add a college by its UUID
+    Add {
+        college_uuid:     Uuid,
+        #[clap(long)]
+        interest_level:   Option<u32>,
+        #[clap(long)]
+        expected_outcome: Option<u32>,
+    },
+    /// change a school on the list, by its id (not the college's)
+    Update {
+        id:               u32,
+        #[clap(long)]
+        interest_level:   Option<u32>,
+        #[clap(long)]
+        expected_outcome: Option<u32>,
+    },
+    /// take a school off the list, by its id
+    Remove { id: u32 },
+}
+
+#[derive(Subcommand, Debug)]
+enum ProfileCommand {
+    /// save --key and/or --username/--password/--school under a name
+    Add {
+        name:            String,
+        #[clap(long)]
+        student_name:    Option<String>,
+        #[clap(long)]
+        graduation_year: Option<u32>,
+    },
+    List,
+    /// use this profile when --profile isn't given
+    Use { name: String },
+    Remove { name: String },
+}
+
+fn open_store(opts: &Opts) -> Result<CredentialStore> {
+    let path = match &opts.store {
+        Some(path) => path.clone(),
+        None => CredentialStore::default_path()
+            .ok_or_else(|| anyhow::anyhow!("No config dir found - pass --store"))?,
+    };
+    let passphrase = match &opts.passphrase {
+        Some(passphrase) => passphrase.clone(),
+        None => {
+            let mut passphrase = match std::io::stdin().is_terminal() {
+                // Without echo, so it doesn't end up in scrollback
+                true => {
+                    let term = Term::stderr();
+                    term.write_str(&format!("Passphrase for {}: ", path.display()))?;
+                    term.read_secure_line()?
+                }
+                // Piped in, e.g. from a password manager
+                false => {
+                    let mut passphrase = String::new();
+                    std::io::stdin().read_line(&mut passphrase)?;
+                    passphrase
+                }
+            };
+            passphrase.truncate(passphrase.trim_end_matches(['\r', '\n']).len());
+            SecretKey::from(passphrase)
+        }
+    };
+    Ok(CredentialStore::open(path, passphrase)?)
+}
 
-    let client = Client::new_with_client(opts.key, c).await?;
+async fn schools_command(client: &Client, command: SchoolsCommand) -> Result<()> {
+    let show = |school: Write<School>| match school {
+        Write::Done(school) => println!(
+            "{}\t{}",
+            school.id.map(|id| id.to_string()).unwrap_or("-".to_string()),
+            school
+                .college
+                .and_then(|c| c.name)
+                .unwrap_or("NO NAME".to_string())
+        ),
+        Write::DryRun(request) => println!("{request}"),
+    };
+    match command {
+        SchoolsCommand::Add {
+            college_uuid,
+            interest_level,
+            expected_outcome,
+        } => {
+            let school = NewSchool {
+                college_uuid,
+                interest_level,
+                expected_outcome,
+            };
+            show(client.add_school_im_thinking_about(&school).await?);
+        }
+        SchoolsCommand::Update {
+            id,
+            interest_level,
+            expected_outcome,
+        } => {
+            let update = SchoolUpdate {
+                id,
+                interest_level,
+                expected_outcome,
+            };
+            show(client.update_school_im_thinking_about(&update).await?);
+        }
+        SchoolsCommand::Remove { id } => match client.remove_school_im_thinking_about(id).await? {
+            Write::Done(()) => println!("Removed {id}"),
+            Write::DryRun(request) => println!("{request}"),
+        },
+    }
+    Ok(())
+}
+
+async fn sync_command(client: &Client, file: PathBuf, yes: bool) -> Result<()> {
+    let targets = TargetList::load(&file)?;
+    let plan = SyncPlan::fetch(client, &targets).await?;
+    println!("{plan}");
+    if plan.is_empty() {
+        return Ok(());
+    }
+    if !yes && !client.is_dry_run() {
+        eprintln!("Apply {} changes? [y/N]", plan.changes.len());
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !answer.trim().eq_ignore_ascii_case("y") {
+            anyhow::bail!("Nothing changed");
+        }
+    }
+    for write in plan.apply(client).await? {
+        if let Write::DryRun(request) = write {
+            println!("{request}");
+        }
+    }
+    Ok(())
+}
 
-    let schools = client.get_schools_im_thinking_about().await?;
+/// Prints admission stats for every school on the thinking-about list
+async fn stats_command(client: &Client) -> Result<()> {
+    let schools = client.get_all_schools_im_thinking_about().await?;
 
-    stream::iter(schools.data.into_iter())
+    stream::iter(schools.into_iter())
         .map(|school| {
             let client = client.clone();
             tokio::spawn(async move {
@@ -177,3 +374,171 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+fn profile_command(opts: &Opts, command: ProfileCommand) -> Result<()> {
+    let mut store = open_store(opts)?;
+    match command {
+        ProfileCommand::Add {
+            name,
+            student_name,
+            graduation_year,
+        } => {
+            let login = match (&opts.username, &opts.password) {
+                (Some(username), Some(password)) => Some(StoredLogin {
+                    username: username.clone(),
+                    password: password.clone(),
+                    school:   opts.school.clone(),
+                }),
+                _ => None,
+            };
+            if opts.key.is_none() && login.is_none() {
+                anyhow::bail!("Nothing to save - pass --key and/or --username/--password");
+            }
+            let profile = Profile {
+                token: opts.key.clone(),
+                login,
+                student: StudentInfo {
+                    name: student_name,
+                    school: opts.school.clone(),
+                    graduation_year,
+                },
+            };
+            store.insert(&name, profile);
+            if store.active().is_none() {
+                store.set_active(&name)?;
+            }
+            println!("Saved {name}");
+        }
+        ProfileCommand::List => {
+            let active = store.active().map(|(name, _)| name.to_string());
+            for (name, profile) in store.profiles() {
+                let marker = if active.as_deref() == Some(name) { "*" } else { " " };
+                let student = &profile.student;
+                println!(
+                    "{marker} {name}\t{}\t{}\t{}",
+                    student.name.as_deref().unwrap_or("-"),
+                    student.school.as_deref().unwrap_or("-"),
+                    student
+                        .graduation_year
+                        .map(|year| year.to_string())
+                        .unwrap_or("-".to_string()),
+                );
+            }
+            return Ok(());
+        }
+        ProfileCommand::Use { name } => {
+            store.set_active(&name)?;
+            println!("Using {name}");
+        }
+        ProfileCommand::Remove { name } => match store.remove(&name) {
+            Some(_) => println!("Removed {name}"),
+            None => anyhow::bail!("No profile named {name}"),
+        },
+    }
+    store.save()?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok(); // Contains KEY
+    let mut opts = Opts::parse();
+    LogTracer::init().expect("Failed to initialize LogTracer");
+    let subscriber = fmt::Subscriber::builder()
+        .with_env_filter(EnvFilter::from_default_env())
+        .finish();
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("Failed to set global tracing subscriber");
+
+    if let Some(Command::Profile(command)) = opts.command.take() {
+        return profile_command(&opts, command);
+    }
+
+    // HTTP client
+    let c = ClientBuilder::new()
+        .use_rustls_tls()
+        .https_only(true)
+        .brotli(true)
+        .gzip(true)
+        .zstd(true)
+        .deflate(true)
+        .build()?;
+
+    let mut builder = Client::builder()
+        .http_client(c)
+        .rate_limit(RateLimit {
+            requests_per_second: opts.rps,
+            burst:               opts.rps.ceil().max(1.) as u32,
+            max_in_flight:       opts.max_in_flight,
+        });
+    // Saved profiles are the fallback when no credentials are passed in
+    let has_credentials = opts.key.is_some()
+        || opts.key_file.is_some()
+        || opts.username.is_some()
+        || opts.sso;
+    let store_exists = match &opts.store {
+        Some(path) => path.exists(),
+        None => CredentialStore::default_path().is_some_and(|path| path.exists()),
+    };
+    if opts.profile.is_some() || (!has_credentials && store_exists) {
+        let store = open_store(&opts)?;
+        let profile = match &opts.profile {
+            Some(name) => store
+                .get(name)
+                .ok_or_else(|| anyhow::anyhow!("No profile named {name}"))?,
+            None => match store.active() {
+                Some((name, profile)) => {
+                    info!("Using profile {name}");
+                    profile
+                }
+                None => anyhow::bail!("No key given and no profile in use"),
+            },
+        };
+        builder = profile.apply(builder);
+    }
+    if let Some(key) = opts.key {
+        builder = builder.key(key);
+    }
+    if let Some(path) = opts.key_file {
+        builder = builder.credentials(FileKey(path));
+    }
+    if let (Some(username), Some(password)) = (opts.username, opts.password) {
+        builder = builder.login(match opts.school {
+            Some(school) => Login::school_password(school, username, password),
+            None => Login::password(username, password),
+        });
+    } else if let (true, Some(school)) = (opts.sso, &opts.school) {
+        let redirect_uri = Url::parse("https://student.naviance.com/")?;
+        let url = builder.sso_url(school, &redirect_uri).await?;
+        eprintln!("Log in at {url}\nthen paste the URL you end up on:");
+        let mut redirect = String::new();
+        std::io::stdin().read_line(&mut redirect)?;
+        builder = builder.login(Login::SsoRedirect(Url::parse(redirect.trim())?));
+    }
+    if let Some(path) = opts.record {
+        builder = builder.record(path);
+    }
+    if let Some(path) = opts.replay {
+        builder = builder.replay(path);
+    }
+    if let Some(dir) = opts.cache_dir {
+        let mode = match (opts.offline, opts.refresh) {
+            (true, _) => CacheMode::CacheOnly,
+            (_, true) => CacheMode::Refresh,
+            _ => CacheMode::Normal,
+        };
+        builder = builder.cache(ResponseCache::new(dir).mode(mode));
+    }
+    let client = builder.dry_run(opts.dry_run).build().await?;
+    if let Some(expires_at) = client.key_expires_at() {
+        info!("API key expires at {expires_at}");
+    }
+    let result = match opts.command {
+        Some(Command::Schools(command)) => schools_command(&client, command).await,
+        Some(Command::Sync { file, yes }) => sync_command(&client, file, yes).await,
+        _ => stats_command(&client).await,
+    };
+    // Failed runs are worth replaying too
+    client.save_cassette().await?;
+    result
+}
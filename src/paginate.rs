@@ -0,0 +1,83 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use futures::{future, stream, stream::BoxStream, StreamExt, TryStreamExt};
+
+use crate::{types::PagedEndpoint, Client, Result};
+
+/// Walks every page of a [`Paged`](crate::types::Paged) endpoint. Created by
+/// [`Client::paginate`]
+pub struct Paginator<E: PagedEndpoint> {
+    client:      Client,
+    params:      Arc<E::Params>,
+    page_size:   Option<u32>,
+    concurrency: usize,
+    _marker:     PhantomData<fn() -> E>,
+}
+
+impl<E: PagedEndpoint> Paginator<E> {
+    pub(crate) fn new(client: Client, params: E::Params) -> Self {
+        Self {
+            client,
+            params: Arc::new(params),
+            page_size: None,
+            concurrency: 1,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Items per page. Defaults to whatever the API defaults to
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// How many pages to fetch at once after the first one. Items still come
+    /// out in order
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Every item on every page, in order
+    pub fn stream(self) -> BoxStream<'static, Result<E::Item>> {
+        let Self {
+            client,
+            params,
+            page_size,
+            concurrency,
+            ..
+        } = self;
+        let first = {
+            let (client, params) = (client.clone(), params.clone());
+            async move { client.fetch_page::<E>(&params, 1, page_size).await }
+        };
+        stream::once(first)
+            .map_ok(move |first| {
+                // The first page tells us how many more there are
+                let limit = page_size.or(first.limit);
+                let total_pages = match (first.total_pages, first.total_items, limit) {
+                    (Some(total_pages), ..) => total_pages,
+                    (None, Some(total_items), Some(limit)) if limit > 0 => {
+                        total_items.div_ceil(limit)
+                    }
+                    _ => 1,
+                };
+                let (client, params) = (client.clone(), params.clone());
+                let rest = stream::iter(2..=total_pages)
+                    .map(move |page| {
+                        let (client, params) = (client.clone(), params.clone());
+                        async move { client.fetch_page::<E>(&params, page, limit).await }
+                    })
+                    .buffered(concurrency);
+                stream::once(future::ready(Ok(first)))
+                    .chain(rest)
+                    .map_ok(|page| stream::iter(page.data.into_iter().map(Ok)))
+                    .try_flatten()
+            })
+            .try_flatten()
+            .boxed()
+    }
+
+    /// Every item on every page, in order, all at once
+    pub async fn collect_all(self) -> Result<Vec<E::Item>> { self.stream().try_collect().await }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use tokio::{
+    sync::{Mutex, Semaphore, SemaphorePermit},
+    time::Instant,
+};
+
+/// Client-side limits on how hard we hit the API
+///
+/// Shared by every clone of a [`Client`](crate::Client)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    /// Sustained requests per second - anything `<= 0` means unlimited
+    pub requests_per_second: f64,
+    /// How many requests can go out back-to-back after being idle
+    pub burst:               u32,
+    /// Maximum number of requests in flight at once, counting each until its
+    /// response body has been read
+    pub max_in_flight:       usize,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 5.,
+            burst:               5,
+            max_in_flight:       8,
+        }
+    }
+}
+
+/// Token bucket + in-flight semaphore
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit:     RateLimit,
+    bucket:    Mutex<Bucket>,
+    in_flight: Semaphore,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last:   Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        let bucket = Bucket {
+            tokens: limit.burst.max(1) as f64,
+            last:   Instant::now(),
+        };
+        Self {
+            in_flight: Semaphore::new(limit.max_in_flight.max(1)),
+            bucket: Mutex::new(bucket),
+            limit,
+        }
+    }
+
+    /// Waits for both a free in-flight slot and a token. The returned permit
+    /// should be held until the response comes back
+    pub(crate) async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit = self
+            .in_flight
+            .acquire()
+            .await
+            .expect("rate limiter semaphore is never closed");
+        if self.limit.requests_per_second <= 0. {
+            return permit;
+        }
+        let capacity = self.limit.burst.max(1) as f64;
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.last).as_secs_f64();
+                bucket.tokens =
+                    (bucket.tokens + elapsed * self.limit.requests_per_second).min(capacity);
+                bucket.last = now;
+                if bucket.tokens >= 1. {
+                    bucket.tokens -= 1.;
+                    return permit;
+                }
+                (1. - bucket.tokens) / self.limit.requests_per_second
+            };
+            tokio::time::sleep(Duration::from_secs_f64(wait)).await;
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use http::{header::RETRY_AFTER, Method, StatusCode};
+use rand::Rng;
+use reqwest::ResponseBuilderExt;
+use tracing::warn;
+
+use crate::{ratelimit::RateLimiter, Error, Result};
+
+/// How (and whether) failed requests get retried
+///
+/// Only idempotent requests are ever retried. A request is retried on
+/// connection errors, timeouts, `408`, `429` and `5xx` responses
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one - `1` disables retries
+    pub max_attempts:        u32,
+    /// Delay before the first retry
+    pub initial_backoff:     Duration,
+    /// Upper bound for any single delay. If `Retry-After` asks for longer,
+    /// we give up instead of retrying too early: with [`Error::RateLimited`]
+    /// for a `429`, or whatever the status maps to otherwise
+    pub max_backoff:         Duration,
+    /// Factor the delay grows by after every attempt
+    pub multiplier:          f64,
+    /// Randomize each delay between half and all of its computed value
+    pub jitter:              bool,
+    /// Wait for as long as the server asks to via `Retry-After`
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts:        4,
+            initial_backoff:     Duration::from_millis(500),
+            max_backoff:         Duration::from_secs(30),
+            multiplier:          2.,
+            jitter:              true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry anything
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// The delay before retry number `attempt` (starting at 1), or an error
+    /// if the server wants us to wait longer than `max_backoff`
+    fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Result<Duration> {
+        if let (true, Some(retry_after)) = (self.respect_retry_after, retry_after) {
+            return match retry_after <= self.max_backoff {
+                true => Ok(retry_after),
+                false => Err(Error::RateLimited {
+                    retry_after: Some(retry_after),
+                }),
+            };
+        }
+        let factor = self.multiplier.powi(attempt.saturating_sub(1) as i32);
+        let delay = self.initial_backoff.mul_f64(factor).min(self.max_backoff);
+        Ok(match self.jitter {
+            true => {
+                let half = delay / 2;
+                half + half.mul_f64(rand::thread_rng().gen::<f64>())
+            }
+            false => delay,
+        })
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE | Method::TRACE
+    )
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status.is_server_error()
+}
+
+fn is_retryable_error(error: &reqwest::Error) -> bool { error.is_timeout() || error.is_connect() }
+
+/// Parses a `Retry-After` header, which is either a number of seconds or an
+/// HTTP date
+pub(crate) fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Sends `request` and reads the whole body before handing the response
+/// back, so a rate limit permit held around this covers the download too
+async fn send(
+    client: &reqwest::Client,
+    request: reqwest::Request,
+) -> reqwest::Result<reqwest::Response> {
+    let response = client.execute(request).await?;
+    let mut buffered = http::Response::builder()
+        .status(response.status())
+        .url(response.url().clone());
+    if let Some(headers) = buffered.headers_mut() {
+        *headers = response.headers().clone();
+    }
+    let body = response.bytes().await?;
+    Ok(buffered
+        .body(body)
+        .expect("status and headers came from a valid response")
+        .into())
+}
+
+/// Sends `request`, retrying according to `policy`. Every attempt goes
+/// through `limiter` separately, and the response body has been read by the
+/// time it's returned
+///
+/// The last response is returned as-is, even if it's an error status, unless
+/// it's a `429` whose `Retry-After` asks for a longer wait than the policy
+/// allows
+pub(crate) async fn execute(
+    client: &reqwest::Client,
+    policy: &RetryPolicy,
+    limiter: Option<&RateLimiter>,
+    mut request: reqwest::Request,
+) -> Result<reqwest::Response> {
+    let idempotent = is_idempotent(request.method());
+    let mut attempt = 1;
+    loop {
+        // Requests with streaming bodies can't be cloned, so they only get one shot
+        let next = match idempotent && attempt < policy.max_attempts {
+            true => request.try_clone(),
+            false => None,
+        };
+        let url = request.url().clone();
+        let result = match limiter {
+            Some(limiter) => {
+                let _permit = limiter.acquire().await;
+                send(client, request).await
+            }
+            None => send(client, request).await,
+        };
+        let Some(next) = next else {
+            return Ok(result?);
+        };
+        let delay = match &result {
+            Ok(response) if is_retryable_status(response.status()) => {
+                warn!(
+                    "{url} returned {} (attempt {attempt}/{})",
+                    response.status(),
+                    policy.max_attempts
+                );
+                match policy.backoff(attempt, retry_after(response)) {
+                    Ok(delay) => delay,
+                    Err(e) if response.status() == StatusCode::TOO_MANY_REQUESTS => return Err(e),
+                    // Left for the caller to turn into the right error
+                    Err(_) => return Ok(result?),
+                }
+            }
+            Err(e) if is_retryable_error(e) => {
+                warn!(
+                    "{url} failed: {e} (attempt {attempt}/{})",
+                    policy.max_attempts
+                );
+                policy.backoff(attempt, None)?
+            }
+            _ => return Ok(result?),
+        };
+        tokio::time::sleep(delay).await;
+        request = next;
+        attempt += 1;
+    }
+}
@@ -0,0 +1,76 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroize;
+
+/// A student's API key (bearer token), or any other secret on its way to
+/// becoming one (passwords, SSO codes)
+///
+/// Redacted in `Debug`/`Display` so it can't leak through `{:?}` or tracing
+/// fields, and wiped from memory when dropped. Use [`SecretKey::expose`] when
+/// you really need the raw value
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretKey(String);
+
+impl SecretKey {
+    pub fn new(key: impl Into<String>) -> Self { Self(key.into()) }
+
+    /// The raw key - don't log this
+    pub fn expose(&self) -> &str { &self.0 }
+
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) { self.0.zeroize(); }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey([REDACTED])")
+    }
+}
+
+impl fmt::Display for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("[REDACTED]") }
+}
+
+impl From<String> for SecretKey {
+    fn from(key: String) -> Self { Self(key) }
+}
+
+impl From<&str> for SecretKey {
+    fn from(key: &str) -> Self { Self(key.to_string()) }
+}
+
+/// So it can be parsed straight from the command line or environment
+impl FromStr for SecretKey {
+    type Err = Infallible;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> { Ok(key.into()) }
+}
+
+/// There's deliberately no `Serialize` - writing one out has to go through
+/// [`SecretKey::expose`]
+impl<'de> Deserialize<'de> for SecretKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+/// For things that carry a secret and still have to serialize it as-is,
+/// e.g. responses for drift detection or the encrypted credential store
+pub(crate) fn serialize_exposed<S: Serializer>(
+    key: &SecretKey,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(key.expose())
+}
+
+/// [`serialize_exposed`] for optional secrets
+pub(crate) fn serialize_exposed_opt<S: Serializer>(
+    key: &Option<SecretKey>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    key.as_ref().map(SecretKey::expose).serialize(serializer)
+}
@@ -0,0 +1,264 @@
+use std::{
+    collections::BTreeMap,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::{
+    login::Login,
+    secret::{serialize_exposed, serialize_exposed_opt},
+    ClientBuilder, Error, Result, SecretKey,
+};
+
+/// OWASP's 2023 recommendation for PBKDF2-HMAC-SHA256
+const ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const VERSION: u32 = 1;
+
+/// Named sets of credentials for everyone the tool gets run for, kept in a
+/// passphrase-encrypted file
+///
+/// ```no_run
+/// # async fn run() -> naviance::Result<()> {
+/// use naviance::{store::CredentialStore, Client};
+///
+/// let path = CredentialStore::default_path().unwrap();
+/// let store = CredentialStore::open(path, "passphrase")?;
+/// let profile = store.get("alice").unwrap();
+/// let client = profile.apply(Client::builder()).build().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CredentialStore {
+    path:       PathBuf,
+    passphrase: SecretKey,
+    contents:   Contents,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Contents {
+    active:   Option<String>,
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// Everything needed to act as one student
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(serialize_with = "serialize_exposed_opt", default)]
+    pub token:   Option<SecretKey>,
+    /// For logging in again once `token` expires
+    #[serde(default)]
+    pub login:   Option<StoredLogin>,
+    #[serde(default)]
+    pub student: StudentInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredLogin {
+    pub username: String,
+    #[serde(serialize_with = "serialize_exposed")]
+    pub password: SecretKey,
+    pub school:   Option<String>,
+}
+
+/// Notes about who a profile belongs to - never sent anywhere
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StudentInfo {
+    pub name:            Option<String>,
+    pub school:          Option<String>,
+    pub graduation_year: Option<u32>,
+}
+
+/// What's actually on disk
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    version:    u32,
+    iterations: u32,
+    salt:       String,
+    nonce:      String,
+    ciphertext: String,
+}
+
+impl Profile {
+    /// Sets `builder` up with this profile's token and/or login
+    pub fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        if let Some(login) = &self.login {
+            builder = builder.login(match &login.school {
+                Some(school) => {
+                    Login::school_password(school, &login.username, login.password.clone())
+                }
+                None => Login::password(&login.username, login.password.clone()),
+            });
+        }
+        if let Some(token) = &self.token {
+            builder = builder.key(token.clone());
+        }
+        builder
+    }
+}
+
+impl CredentialStore {
+    /// `naviance/credentials.enc` under the user's config directory
+    pub fn default_path() -> Option<PathBuf> {
+        Some(config_dir()?.join("naviance").join("credentials.enc"))
+    }
+
+    /// Opens the store at `path`, or starts an empty one if there's nothing
+    /// there yet
+    pub fn open(path: impl Into<PathBuf>, passphrase: impl Into<SecretKey>) -> Result<Self> {
+        let (path, passphrase) = (path.into(), passphrase.into());
+        let contents = match std::fs::read(&path) {
+            Ok(file) => decrypt(&file, &passphrase)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Contents::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path,
+            passphrase,
+            contents,
+        })
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Writes the store back to disk (readable only by the current user on
+    /// Unix)
+    pub fn save(&self) -> Result<()> {
+        let file = encrypt(&self.contents, &self.passphrase)?;
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        std::io::Write::write_all(&mut options.open(&self.path)?, &file)?;
+        Ok(())
+    }
+
+    pub fn profiles(&self) -> impl Iterator<Item = (&str, &Profile)> {
+        self.contents
+            .profiles
+            .iter()
+            .map(|(name, profile)| (name.as_str(), profile))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> { self.contents.profiles.get(name) }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Profile> {
+        self.contents.profiles.get_mut(name)
+    }
+
+    /// Adds or replaces a profile
+    pub fn insert(&mut self, name: impl Into<String>, profile: Profile) {
+        self.contents.profiles.insert(name.into(), profile);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Profile> {
+        if self.contents.active.as_deref() == Some(name) {
+            self.contents.active = None;
+        }
+        self.contents.profiles.remove(name)
+    }
+
+    /// The profile to use when none is asked for
+    pub fn active(&self) -> Option<(&str, &Profile)> {
+        let name = self.contents.active.as_deref()?;
+        Some((name, self.get(name)?))
+    }
+
+    pub fn set_active(&mut self, name: &str) -> Result<()> {
+        if !self.contents.profiles.contains_key(name) {
+            return Err(Error::Store(format!("No profile named {name}")));
+        }
+        self.contents.active = Some(name.to_string());
+        Ok(())
+    }
+}
+
+fn encrypt(contents: &Contents, passphrase: &SecretKey) -> Result<Vec<u8>> {
+    let rng = SystemRandom::new();
+    let (mut salt, mut nonce) = ([0; SALT_LEN], [0; NONCE_LEN]);
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| Error::Store("No randomness available".to_string()))?;
+    let mut data = serde_json::to_vec(contents)?;
+    cipher(passphrase, &salt, ITERATIONS)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| Error::Store("Encryption failed".to_string()))?;
+    let envelope = Envelope {
+        version:    VERSION,
+        iterations: ITERATIONS,
+        salt:       STANDARD.encode(salt),
+        nonce:      STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(&data),
+    };
+    Ok(serde_json::to_vec_pretty(&envelope)?)
+}
+
+fn decrypt(file: &[u8], passphrase: &SecretKey) -> Result<Contents> {
+    let envelope: Envelope = serde_json::from_slice(file)?;
+    if envelope.version != VERSION {
+        return Err(Error::Store(format!(
+            "Unsupported store version {}",
+            envelope.version
+        )));
+    }
+    let decode = |field: &str| {
+        STANDARD
+            .decode(field)
+            .map_err(|e| Error::Store(format!("Corrupt store: {e}")))
+    };
+    let (salt, mut data) = (decode(&envelope.salt)?, decode(&envelope.ciphertext)?);
+    let nonce = Nonce::try_assume_unique_for_key(&decode(&envelope.nonce)?)
+        .map_err(|_| Error::Store("Corrupt store: bad nonce".to_string()))?;
+    let plaintext = cipher(passphrase, &salt, envelope.iterations)?
+        .open_in_place(nonce, Aad::empty(), &mut data)
+        .map_err(|_| Error::Store("Wrong passphrase (or a corrupt store)".to_string()))?;
+    let contents = serde_json::from_slice(plaintext);
+    data.zeroize();
+    Ok(contents?)
+}
+
+fn cipher(passphrase: &SecretKey, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or(Error::Store("Corrupt store: zero iterations".to_string()))?;
+    let mut key = [0; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.expose().as_bytes(),
+        &mut key,
+    );
+    let unbound = UnboundKey::new(&CHACHA20_POLY1305, &key);
+    key.zeroize();
+    let key = unbound.map_err(|_| Error::Store("Bad key length".to_string()))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Where per-user config lives on this platform
+fn config_dir() -> Option<PathBuf> {
+    let env = |var| {
+        std::env::var_os(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if cfg!(windows) {
+        env("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        Some(env("HOME")?.join("Library/Application Support"))
+    } else {
+        env("XDG_CONFIG_HOME").or_else(|| Some(env("HOME")?.join(".config")))
+    }
+}
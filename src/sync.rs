@@ -0,0 +1,277 @@
+//! Keeping the "colleges I'm thinking about" list in line with a file
+//!
+//! ```no_run
+//! # async fn run(client: naviance::Client) -> naviance::Result<()> {
+//! use naviance::sync::{SyncPlan, TargetList};
+//!
+//! let targets = TargetList::load("targets.toml")?;
+//! let plan = SyncPlan::fetch(&client, &targets).await?;
+//! println!("{plan}");
+//! plan.apply(&client).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::HashSet, fmt, io::Read, path::Path};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    types::{NewSchool, School, SchoolUpdate},
+    Client, Error, Result, Write,
+};
+
+/// One college on a local list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Target {
+    /// The college's UUID, as in [`College::uuid`](crate::types::College::uuid)
+    pub uuid:             Uuid,
+    /// Just for whoever edits the file
+    #[serde(default)]
+    pub name:             Option<String>,
+    /// Left alone on Naviance if not set
+    #[serde(default)]
+    pub interest_level:   Option<u32>,
+    /// Left alone on Naviance if not set
+    #[serde(default)]
+    pub expected_outcome: Option<u32>,
+}
+
+/// The colleges a student should be thinking about, as a counselor keeps
+/// them. Either TOML:
+///
+/// ```toml
+/// [[college]]
+/// uuid = "c5e3c1f4-1a2b-4c3d-8e9f-0a1b2c3d4e5f"
+/// name = "Example State"
+/// interest_level = 3
+/// ```
+///
+/// or CSV with a `uuid,name,interest_level,expected_outcome` header
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TargetList {
+    #[serde(rename = "college", default)]
+    pub colleges: Vec<Target>,
+}
+
+impl TargetList {
+    /// Reads a `.toml` or `.csv` file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        match extension.to_ascii_lowercase().as_str() {
+            "toml" => Self::from_toml(&std::fs::read_to_string(path)?),
+            "csv" => Self::from_csv(std::fs::File::open(path)?),
+            _ => Err(Error::TargetList(format!(
+                "Don't know how to read {} - use .toml or .csv",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let list: Self = toml::from_str(toml).map_err(|e| Error::TargetList(e.to_string()))?;
+        list.validate()
+    }
+
+    pub fn from_csv(csv: impl Read) -> Result<Self> {
+        let colleges = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(csv)
+            .deserialize()
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|e| Error::TargetList(e.to_string()))?;
+        Self { colleges }.validate()
+    }
+
+    // Two lines for one college would fight over its interest level
+    fn validate(self) -> Result<Self> {
+        let mut seen = HashSet::new();
+        if let Some(dup) = self.colleges.iter().find(|c| !seen.insert(c.uuid)) {
+            return Err(Error::TargetList(format!("{} is listed twice", dup.uuid)));
+        }
+        Ok(self)
+    }
+}
+
+/// One step towards making the remote list match the local one
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Add {
+        name:   Option<String>,
+        school: NewSchool,
+    },
+    Update {
+        name:             Option<String>,
+        /// What it was before
+        interest_level:   Option<u32>,
+        expected_outcome: Option<u32>,
+        update:           SchoolUpdate,
+    },
+    Remove {
+        name: Option<String>,
+        /// [`School::id`]
+        id:   u32,
+    },
+}
+
+/// Everything that has to change on Naviance for the list to match a
+/// [`TargetList`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncPlan {
+    pub changes: Vec<Change>,
+}
+
+impl SyncPlan {
+    /// Diffs `local` against the `remote` list. Schools without a college
+    /// UUID can't be matched up, so they're left alone
+    pub fn new(local: &TargetList, remote: &[School]) -> Self {
+        let mut changes = Vec::new();
+        let mut matched = HashSet::new();
+        for target in &local.colleges {
+            let found = remote
+                .iter()
+                .find(|s| s.college.as_ref().and_then(|c| c.uuid) == Some(target.uuid));
+            let Some(school) = found else {
+                changes.push(Change::Add {
+                    name:   target.name.clone(),
+                    school: NewSchool {
+                        college_uuid:     target.uuid,
+                        interest_level:   target.interest_level,
+                        expected_outcome: target.expected_outcome,
+                    },
+                });
+                continue;
+            };
+            matched.insert(target.uuid);
+            let Some(id) = school.id else {
+                warn!(
+                    "{} is on the list without an id, can't update it",
+                    target.uuid
+                );
+                continue;
+            };
+            let differs =
+                |wanted: Option<u32>, have: Option<u32>| wanted.filter(|w| have != Some(*w));
+            let update = SchoolUpdate {
+                id,
+                interest_level: differs(target.interest_level, school.interest_level),
+                expected_outcome: differs(target.expected_outcome, school.expected_outcome),
+            };
+            if update.interest_level.is_some() || update.expected_outcome.is_some() {
+                changes.push(Change::Update {
+                    name: target.name.clone().or_else(|| college_name(school)),
+                    interest_level: school.interest_level,
+                    expected_outcome: school.expected_outcome,
+                    update,
+                });
+            }
+        }
+        for school in remote {
+            let uuid = school.college.as_ref().and_then(|c| c.uuid);
+            match (uuid, school.id) {
+                (Some(uuid), Some(id)) if !matched.contains(&uuid) => {
+                    changes.push(Change::Remove {
+                        name: college_name(school),
+                        id,
+                    })
+                }
+                (None, _) => warn!(
+                    "Leaving school {:?} alone, it has no college UUID",
+                    school.id
+                ),
+                _ => {}
+            }
+        }
+        Self { changes }
+    }
+
+    /// Fetches the whole remote list and diffs `local` against it
+    pub async fn fetch(client: &Client, local: &TargetList) -> Result<Self> {
+        let remote = client.get_all_schools_im_thinking_about().await?;
+        Ok(Self::new(local, &remote))
+    }
+
+    pub fn is_empty(&self) -> bool { self.changes.is_empty() }
+
+    /// Makes the changes one at a time, stopping at the first failure. A
+    /// [dry-run](crate::ClientBuilder::dry_run) client hands back what it
+    /// would have sent instead
+    pub async fn apply(&self, client: &Client) -> Result<Vec<Write<()>>> {
+        let mut done = Vec::with_capacity(self.changes.len());
+        for change in &self.changes {
+            let write = match change {
+                Change::Add { school, .. } => client
+                    .add_school_im_thinking_about(school)
+                    .await?
+                    .map(|_| ()),
+                Change::Update { update, .. } => client
+                    .update_school_im_thinking_about(update)
+                    .await?
+                    .map(|_| ()),
+                Change::Remove { id, .. } => client.remove_school_im_thinking_about(*id).await?,
+            };
+            if !write.is_dry_run() {
+                info!("Applied: {change}");
+            }
+            done.push(write);
+        }
+        Ok(done)
+    }
+}
+
+fn college_name(school: &School) -> Option<String> { school.college.as_ref()?.name.clone() }
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |name: &Option<String>| name.clone().unwrap_or("NO NAME".to_string());
+        let level = |level: Option<u32>| level.map(|l| l.to_string()).unwrap_or("-".to_string());
+        match self {
+            Self::Add { name: n, school } => {
+                write!(f, "+ {} ({})", name(n), school.college_uuid)?;
+                if let Some(interest_level) = school.interest_level {
+                    write!(f, " interest level {interest_level}")?;
+                }
+                if let Some(expected_outcome) = school.expected_outcome {
+                    write!(f, " expected outcome {expected_outcome}")?;
+                }
+                Ok(())
+            }
+            Self::Update {
+                name: n,
+                interest_level,
+                expected_outcome,
+                update,
+            } => {
+                write!(f, "~ {} (id {})", name(n), update.id)?;
+                if update.interest_level.is_some() {
+                    let to = level(update.interest_level);
+                    write!(f, " interest level {} -> {to}", level(*interest_level))?;
+                }
+                if update.expected_outcome.is_some() {
+                    let to = level(update.expected_outcome);
+                    write!(f, " expected outcome {} -> {to}", level(*expected_outcome))?;
+                }
+                Ok(())
+            }
+            Self::Remove { name: n, id } => write!(f, "- {} (id {id})", name(n)),
+        }
+    }
+}
+
+impl fmt::Display for SyncPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "Nothing to change");
+        }
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
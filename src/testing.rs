@@ -0,0 +1,639 @@
+//! An in-process stand-in for Naviance, for testing [`Client`] end to end
+//! without network access or a real student key
+//!
+//! ```
+//! # #[tokio::main]
+//! # async fn main() -> naviance::Result<()> {
+//! use naviance::testing::MockServer;
+//!
+//! let server = MockServer::start().await;
+//! let client = server.client_builder().build().await?;
+//! let schools = client.get_schools_im_thinking_about().await?;
+//!
+//! server.fail_next("/college/scattergram", 503, 2);
+//! // Succeeds on the third attempt
+//! let sources = client.get_scattergram_sources().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tracing::debug;
+use url::Url;
+
+use crate::{
+    types::{
+        Application, ApplicationStatistics, College, CollegeSearch, CollegesImApplyingTo, Config,
+        Endpoint, PasswordLogin, ScattergramSources, SchoolsImThinkingAbout, SsoLogin,
+    },
+    util::lock,
+    Client, ClientBuilder, RetryPolicy,
+};
+
+/// The key [`MockServer::start`] accepts
+pub const MOCK_KEY: &str = "mock-student-key";
+/// The only username/password the mock login service accepts
+pub const MOCK_USERNAME: &str = "student";
+pub const MOCK_PASSWORD: &str = "correct horse battery staple";
+/// What the mock SSO provider redirects back with
+pub const MOCK_SSO_CODE: &str = "mock-sso-code";
+
+const CONFIG: &str = include_str!("../fixtures/rewritten_config.js");
+const COLLEGES_IM_THINKING_ABOUT: &str =
+    include_str!("../fixtures/colleges_im_thinking_about.json");
+const COLLEGE: &str = include_str!("../fixtures/college.json");
+const COLLEGES_IM_APPLYING_TO: &str = include_str!("../fixtures/colleges_im_applying_to.json");
+const APPLICATION: &str = include_str!("../fixtures/application.json");
+const COLLEGE_SEARCH: &str = include_str!("../fixtures/college_search.json");
+const SCATTERGRAM: &str = include_str!("../fixtures/scattergram.json");
+const APPLICATION_STATISTICS: &str = include_str!("../fixtures/application_statistics.json");
+
+/// A request the server has seen
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method:     String,
+    pub path:       String,
+    pub query:      Option<String>,
+    /// Whether it carried the right bearer token
+    pub authorized: bool,
+    pub body:       String,
+}
+
+#[derive(Debug)]
+struct Failure {
+    path_prefix: String,
+    status:      u16,
+    remaining:   usize,
+    retry_after: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct State {
+    key:      Mutex<String>,
+    base:     Url,
+    /// Exact paths first, then the endpoint's `PATH` for anything below it
+    fixtures: Mutex<HashMap<String, String>>,
+    failures: Mutex<Vec<Failure>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+/// A local HTTP server that serves `rewritten_config.js` and the API
+/// endpoints from fixtures, tagged with `ETag`s so they can be revalidated.
+/// Shuts down when dropped
+#[derive(Debug)]
+pub struct MockServer {
+    addr:  SocketAddr,
+    state: Arc<State>,
+    task:  JoinHandle<()>,
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) { self.task.abort(); }
+}
+
+impl MockServer {
+    /// Starts a server on a random local port that accepts [`MOCK_KEY`]
+    pub async fn start() -> Self { Self::start_with_key(MOCK_KEY).await }
+
+    /// Starts a server on a random local port that accepts `key`
+    pub async fn start_with_key(key: impl Into<String>) -> Self {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .expect("failed to bind mock server");
+        let addr = listener.local_addr().expect("mock server has no address");
+        let base = Url::parse(&format!("http://{addr}/")).expect("invalid mock server URL");
+        let fixtures = [
+            (
+                Config::PATH,
+                CONFIG.trim_end().replace("{{BASE}}", base.as_str()),
+            ),
+            (
+                SchoolsImThinkingAbout::PATH,
+                COLLEGES_IM_THINKING_ABOUT.to_string(),
+            ),
+            (College::PATH, COLLEGE.to_string()),
+            (
+                CollegesImApplyingTo::PATH,
+                COLLEGES_IM_APPLYING_TO.to_string(),
+            ),
+            (Application::PATH, APPLICATION.to_string()),
+            (CollegeSearch::PATH, COLLEGE_SEARCH.to_string()),
+            (ScattergramSources::PATH, SCATTERGRAM.to_string()),
+            (
+                ApplicationStatistics::PATH,
+                APPLICATION_STATISTICS.to_string(),
+            ),
+        ];
+        let state = Arc::new(State {
+            key: Mutex::new(key.into()),
+            base,
+            fixtures: Mutex::new(
+                fixtures
+                    .into_iter()
+                    .map(|(path, body)| (path.to_string(), body))
+                    .collect(),
+            ),
+            failures: Mutex::default(),
+            requests: Mutex::default(),
+        });
+        let task = tokio::spawn(serve(listener, state.clone()));
+        Self { addr, state, task }
+    }
+
+    pub fn addr(&self) -> SocketAddr { self.addr }
+
+    /// Serves both `rewritten_config.js` and the API
+    pub fn url(&self) -> Url { self.state.base.clone() }
+
+    /// The key it currently accepts (and hands out on login)
+    pub fn key(&self) -> String { lock(&self.state.key).clone() }
+
+    /// Rotates the key, so the old one starts getting `401`s
+    pub fn set_key(&self, key: impl Into<String>) { *lock(&self.state.key) = key.into(); }
+
+    /// A [`ClientBuilder`] pointed at this server with its key, plain HTTP
+    /// allowed and quick retries
+    pub fn client_builder(&self) -> ClientBuilder {
+        Client::builder()
+            .key(self.key())
+            .student_base(self.url())
+            .https_only(false)
+            .retry(RetryPolicy {
+                initial_backoff: Duration::from_millis(10),
+                max_backoff: Duration::from_millis(100),
+                ..RetryPolicy::default()
+            })
+    }
+
+    /// Serves `body` for `path`. Setting an endpoint's `PATH` (e.g.
+    /// `/college/uuid`) covers everything below it that doesn't have its own
+    /// fixture
+    pub fn set_fixture(&self, path: impl Into<String>, body: impl Into<String>) {
+        lock(&self.state.fixtures).insert(path.into(), body.into());
+    }
+
+    /// Stops serving `path`, so it 404s
+    pub fn remove_fixture(&self, path: &str) { lock(&self.state.fixtures).remove(path); }
+
+    /// Answers the next `times` requests under `path_prefix` with `status`
+    /// (and an empty JSON body) instead
+    pub fn fail_next(&self, path_prefix: impl Into<String>, status: u16, times: usize) {
+        self.push_failure(path_prefix.into(), status, times, None);
+    }
+
+    /// Answers the next `times` requests under `path_prefix` with a `429` and
+    /// a `Retry-After` header
+    pub fn rate_limit_next(
+        &self,
+        path_prefix: impl Into<String>,
+        times: usize,
+        retry_after: Duration,
+    ) {
+        self.push_failure(path_prefix.into(), 429, times, Some(retry_after));
+    }
+
+    /// Answers the next `times` requests under `path_prefix` with a `503` and
+    /// a `Retry-After` header, like a server that's down for maintenance
+    pub fn unavailable_next(
+        &self,
+        path_prefix: impl Into<String>,
+        times: usize,
+        retry_after: Duration,
+    ) {
+        self.push_failure(path_prefix.into(), 503, times, Some(retry_after));
+    }
+
+    fn push_failure(
+        &self,
+        path_prefix: String,
+        status: u16,
+        remaining: usize,
+        retry_after: Option<Duration>,
+    ) {
+        lock(&self.state.failures).push(Failure {
+            path_prefix,
+            status,
+            remaining,
+            retry_after,
+        });
+    }
+
+    /// Every request seen so far, oldest first
+    pub fn requests(&self) -> Vec<RecordedRequest> { lock(&self.state.requests).clone() }
+}
+
+async fn serve(listener: TcpListener, state: Arc<State>) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, &state).await {
+                debug!("Mock server connection failed: {e}");
+            }
+        });
+    }
+}
+
+/// A parsed HTTP/1.1 request - just enough of one for our client
+struct Request {
+    method:  String,
+    path:    String,
+    query:   Option<String>,
+    headers: HashMap<String, String>,
+    body:    String,
+}
+
+/// What to send back
+struct Response {
+    status:       u16,
+    content_type: &'static str,
+    headers:      Vec<(&'static str, String)>,
+    body:         String,
+}
+
+impl Response {
+    fn json(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream, state: &State) -> std::io::Result<()> {
+    let Some(request) = read_request(&mut stream).await? else {
+        return Ok(());
+    };
+    let response = respond(state, &request);
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<Request>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 4096];
+    let head_end = loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default();
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    let length = headers
+        .get("content-length")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buf[head_end + 4..].to_vec();
+    while body.len() < length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    Ok(Some(Request {
+        method,
+        path,
+        query,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    }))
+}
+
+fn respond(state: &State, request: &Request) -> Response {
+    let authorized = request.headers.get("authorization").map(String::as_str)
+        == Some(format!("Bearer {}", lock(&state.key)).as_str());
+    lock(&state.requests).push(RecordedRequest {
+        method: request.method.clone(),
+        path: request.path.clone(),
+        query: request.query.clone(),
+        authorized,
+        body: request.body.clone(),
+    });
+
+    if let Some(response) = injected_failure(state, &request.path) {
+        return response;
+    }
+    if let Some(response) = login(state, request) {
+        return response;
+    }
+    if request.path == Config::PATH {
+        return match lock(&state.fixtures).get(Config::PATH) {
+            Some(config) => Response {
+                status:       200,
+                content_type: "application/javascript",
+                headers:      Vec::new(),
+                body:         config.clone(),
+            },
+            None => Response::json(404, "{}"),
+        };
+    }
+    if !authorized {
+        return Response::json(401, r#"{"message":"Unauthorized"}"#);
+    }
+    if let Some(response) = list_write(state, request) {
+        return response;
+    }
+    let fixtures = lock(&state.fixtures);
+    let fixture = fixtures.get(&request.path).or_else(|| {
+        // e.g. /college/uuid/{uuid} falls back to /college/uuid
+        let (parent, id) = request.path.rsplit_once('/')?;
+        let fixture = fixtures.get(parent)?;
+        // ...but /application/{id} only has the one application
+        let fixture_id = id
+            .parse::<u64>()
+            .ok()
+            .and_then(|_| serde_json::from_str::<Value>(fixture).ok()?["id"].as_u64());
+        match fixture_id {
+            Some(fixture_id) if fixture_id.to_string() != id => None,
+            _ => Some(fixture),
+        }
+    });
+    let query = request.query.as_deref();
+    match fixture {
+        Some(body) if request.path == CollegeSearch::PATH => tagged(
+            request,
+            Response::json(200, paginate(&search(body, query), query)),
+        ),
+        Some(body) => tagged(request, Response::json(200, paginate(body, query))),
+        None => Response::json(404, r#"{"message":"Not Found"}"#),
+    }
+}
+
+/// Gives `response` an `ETag` from its body, swapping it for a `304` if the
+/// client says it already has that one
+fn tagged(request: &Request, response: Response) -> Response {
+    let mut hasher = DefaultHasher::new();
+    response.body.hash(&mut hasher);
+    let etag = format!("\"{:x}\"", hasher.finish());
+    let mut response = match request.headers.get("if-none-match") == Some(&etag) {
+        true => Response::json(304, ""),
+        false => response,
+    };
+    response.headers.push(("ETag", etag));
+    response
+}
+
+/// The login service under `/login/` (see `UNIFIED_USER_LOGIN_URL` in the
+/// config fixture): password and SSO code exchanges, plus an SSO provider
+/// that logs everyone in straight away
+fn login(state: &State, request: &Request) -> Option<Response> {
+    let path = request.path.strip_prefix("/login")?;
+    let body = || serde_json::from_str::<Value>(&request.body).unwrap_or_default();
+    let token = || {
+        let key = lock(&state.key).clone();
+        Response::json(200, serde_json::json!({ "token": key }).to_string())
+    };
+    let rejected = || Response::json(401, r#"{"message":"Invalid credentials"}"#);
+    match (request.method.as_str(), path) {
+        ("POST", PasswordLogin::PATH) => {
+            let body = body();
+            Some(
+                match (body["username"].as_str(), body["password"].as_str()) {
+                    (Some(MOCK_USERNAME), Some(MOCK_PASSWORD)) => token(),
+                    _ => rejected(),
+                },
+            )
+        }
+        ("POST", SsoLogin::PATH) => Some(match body()["code"].as_str() {
+            Some(MOCK_SSO_CODE) => token(),
+            _ => rejected(),
+        }),
+        ("GET", "/sso") => {
+            let query = request.query.as_deref().unwrap_or_default();
+            let redirect_uri = url::form_urlencoded::parse(query.as_bytes())
+                .find(|(name, _)| name == "redirect_uri")
+                .and_then(|(_, uri)| Url::parse(&uri).ok())?;
+            let mut location = redirect_uri;
+            location
+                .query_pairs_mut()
+                .append_pair("code", MOCK_SSO_CODE);
+            let mut response = Response::json(302, "{}");
+            response.headers.push(("Location", location.to_string()));
+            Some(response)
+        }
+        _ => None,
+    }
+}
+
+/// Adds, updates and removes schools in the
+/// [`SchoolsImThinkingAbout`] fixture
+fn list_write(state: &State, request: &Request) -> Option<Response> {
+    let target = request.path.strip_prefix(SchoolsImThinkingAbout::PATH)?;
+    let body = serde_json::from_str::<Value>(&request.body).unwrap_or_default();
+    let mut fixtures = lock(&state.fixtures);
+    let list = fixtures.get_mut(SchoolsImThinkingAbout::PATH)?;
+    let mut paged = serde_json::from_str::<Value>(list).ok()?;
+    let data = paged["data"].as_array_mut()?;
+    let position = |data: &[Value], id: &str| {
+        data.iter()
+            .position(|s| s["id"].as_u64().is_some_and(|s| s.to_string() == id))
+    };
+    let not_found = || Response::json(404, r#"{"message":"Not Found"}"#);
+    let response = match (request.method.as_str(), target.strip_prefix('/')) {
+        ("POST", None) if target.is_empty() => {
+            let id = data
+                .iter()
+                .filter_map(|s| s["id"].as_u64())
+                .max()
+                .unwrap_or(0)
+                + 1;
+            let mut college = serde_json::from_str::<Value>(COLLEGE).unwrap_or_default();
+            college["uuid"] = body["collegeUuid"].clone();
+            let school = serde_json::json!({
+                "id": id,
+                "interestLevel": body["interestLevel"],
+                "expectedOutcome": body["expectedOutcome"],
+                "addedByType": 1,
+                "dateAdded": null,
+                "collegeId": college["id"],
+                "college": college,
+                "expectedOutcomeLabel": null,
+                "interestLevelLabel": null,
+            });
+            data.push(school.clone());
+            Response::json(201, school.to_string())
+        }
+        ("PATCH", Some(id)) => match position(data, id) {
+            Some(index) => {
+                let school = &mut data[index];
+                for field in ["interestLevel", "expectedOutcome"] {
+                    if !body[field].is_null() {
+                        school[field] = body[field].clone();
+                    }
+                }
+                Response::json(200, school.to_string())
+            }
+            None => not_found(),
+        },
+        ("DELETE", Some(id)) => match position(data, id) {
+            Some(index) => {
+                data.remove(index);
+                Response::json(204, "")
+            }
+            None => not_found(),
+        },
+        _ => return None,
+    };
+    *list = paged.to_string();
+    Some(response)
+}
+
+fn injected_failure(state: &State, path: &str) -> Option<Response> {
+    let mut failures = lock(&state.failures);
+    let index = failures
+        .iter()
+        .position(|f| f.remaining > 0 && path.starts_with(&f.path_prefix))?;
+    let failure = &mut failures[index];
+    failure.remaining -= 1;
+    let mut response = Response::json(failure.status, "{}");
+    if let Some(retry_after) = failure.retry_after {
+        response
+            .headers
+            .push(("Retry-After", retry_after.as_secs().to_string()));
+    }
+    if failure.remaining == 0 {
+        failures.remove(index);
+    }
+    Some(response)
+}
+
+/// Filters the colleges in a search fixture by `name`, `state`, `country`,
+/// `sector` and `size`. Everything else is ignored
+fn search(body: &str, query: Option<&str>) -> String {
+    let Ok(Value::Object(mut paged)) = serde_json::from_str::<Value>(body) else {
+        return body.to_string();
+    };
+    let Some(Value::Array(data)) = paged.remove("data") else {
+        return body.to_string();
+    };
+    let params: Vec<(String, String)> =
+        url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect();
+    let matches = |college: &Value, name: &str| {
+        let wanted: Vec<&str> = params
+            .iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .collect();
+        let have = match &college[name] {
+            Value::String(have) => have.clone(),
+            have => have.to_string(),
+        };
+        let found = match name {
+            "name" => wanted
+                .iter()
+                .all(|w| have.to_lowercase().contains(&w.to_lowercase())),
+            _ => wanted.contains(&have.as_str()),
+        };
+        wanted.is_empty() || found
+    };
+    let data: Vec<Value> = data
+        .into_iter()
+        .filter(|college| {
+            ["name", "state", "country", "sector", "size"]
+                .iter()
+                .all(|name| matches(college, name))
+        })
+        .collect();
+    paged.insert("totalItems".to_string(), data.len().into());
+    paged.insert("data".to_string(), data.into());
+    Value::Object(paged).to_string()
+}
+
+/// Slices `data` of a paged fixture according to `page`/`limit`
+fn paginate(body: &str, query: Option<&str>) -> String {
+    let Some(query) = query else {
+        return body.to_string();
+    };
+    let Ok(Value::Object(mut paged)) = serde_json::from_str::<Value>(body) else {
+        return body.to_string();
+    };
+    let Some(Value::Array(data)) = paged.remove("data") else {
+        return body.to_string();
+    };
+    let param = |name: &str| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| value.parse::<usize>().ok())
+    };
+    let page = param("page").unwrap_or(1).max(1);
+    let limit = param("limit").unwrap_or(data.len().max(1)).max(1);
+    let total_pages = data.len().div_ceil(limit).max(1);
+    paged.insert("page".to_string(), page.into());
+    paged.insert("limit".to_string(), limit.into());
+    paged.insert("totalItems".to_string(), data.len().into());
+    paged.insert("totalPages".to_string(), total_pages.into());
+    let data: Vec<Value> = data
+        .into_iter()
+        .skip((page - 1) * limit)
+        .take(limit)
+        .collect();
+    paged.insert("data".to_string(), data.into());
+    Value::Object(paged).to_string()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// What a JWT API key says about itself. Nothing here is verified - it's
+/// only for noticing expiry before the server does
+///
+/// `Debug` only lists the names of the other claims, since their values can
+/// be anything from an email address to another token
+#[derive(Clone, PartialEq)]
+pub struct TokenClaims {
+    /// `sub`, usually the student's user id
+    pub subject:    Option<String>,
+    /// `exp`
+    pub expires_at: Option<DateTime<Utc>>,
+    /// `iat`
+    pub issued_at:  Option<DateTime<Utc>>,
+    /// Every claim, including the ones above
+    pub claims:     Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct RawClaims {
+    sub: Option<Value>,
+    exp: Option<f64>,
+    iat: Option<f64>,
+}
+
+impl fmt::Debug for TokenClaims {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenClaims")
+            .field("subject", &self.subject)
+            .field("expires_at", &self.expires_at)
+            .field("issued_at", &self.issued_at)
+            .field("claims", &self.claims.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl TokenClaims {
+    /// Decodes the payload of `key` if it looks like a JWT
+    pub fn decode(key: &str) -> Option<Self> {
+        let mut parts = key.split('.');
+        let (Some(_header), Some(payload), Some(_signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+        let claims: Map<String, Value> = serde_json::from_slice(&payload).ok()?;
+        let raw: RawClaims = serde_json::from_value(Value::Object(claims.clone())).ok()?;
+        let timestamp = |secs: f64| Utc.timestamp_opt(secs as i64, 0).single();
+        Some(Self {
+            subject: raw.sub.map(|sub| match sub {
+                Value::String(sub) => sub,
+                sub => sub.to_string(),
+            }),
+            expires_at: raw.exp.and_then(timestamp),
+            issued_at: raw.iat.and_then(timestamp),
+            claims,
+        })
+    }
+
+    pub fn is_expired(&self) -> bool { self.expires_at.is_some_and(|exp| exp <= Utc::now()) }
+}
@@ -1,112 +1,530 @@
 // use reqwest::Response;
+use std::{borrow::Cow, time::Duration};
+
 use chrono::NaiveDate;
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use url::Url;
 use uuid::Uuid;
 
 use crate::{
-    util::{bool_from_int_opt, none_if_empty_string, none_if_zero, sat_to_act},
-    Error, Result,
+    config_js,
+    util::{
+        self, bool_from_int_opt, decode_json, is_strict, none_if_empty_string, none_if_zero,
+        sat_to_act, url_opt,
+    },
+    Error, Result, SecretKey,
 };
 
+/// An enum of the string values the API is known to send, plus `Unknown`
+/// for anything else. `Unknown` is only allowed in lenient mode, strict mode
+/// fails on values that aren't in the table
+macro_rules! lenient_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $wire:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// Anything we don't know about (only in lenient mode)
+            Unknown,
+        }
+
+        impl $name {
+            const KNOWN: &'static [&'static str] = &[$($wire),*];
+
+            /// What the API calls it
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $wire,)*
+                    Self::Unknown => "Unknown",
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let name = String::deserialize(deserializer)?;
+                match Self::KNOWN.iter().position(|known| *known == name) {
+                    Some(i) => Ok([$(Self::$variant),*][i]),
+                    None if is_strict() => Err(de::Error::unknown_variant(&name, Self::KNOWN)),
+                    None => Ok(Self::Unknown),
+                }
+            }
+        }
+    };
+}
+
+/// An enum for one of the API's integer codes, which is also how it
+/// (de)serializes
+macro_rules! coded_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $code:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+        }
+
+        impl $name {
+            pub fn code(self) -> u32 {
+                match self {
+                    $(Self::$variant => $code,)*
+                }
+            }
+
+            pub fn from_code(code: u32) -> Option<Self> {
+                match code {
+                    $($code => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_u32(self.code())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let code = u32::deserialize(deserializer)?;
+                Self::from_code(code).ok_or_else(|| {
+                    de::Error::invalid_value(
+                        de::Unexpected::Unsigned(code.into()),
+                        &concat!("a ", stringify!($name), " code"),
+                    )
+                })
+            }
+        }
+    };
+}
+
 pub enum EndpointType {
     Public,
     Auth,
 }
 
-pub trait Endpoint // where
+/// Responses and params are `Send + 'static` so that futures from generic
+/// code (e.g. [`Client::fetch_auth`](crate::Client::fetch_auth)) can be
+/// spawned onto a multi-threaded runtime
+pub trait Endpoint: 'static // where
 //     T: DeserializeOwned,
 {
     const PATH: &'static str;
     const METHOD: http::Method;
+    /// How long responses stay fresh in a
+    /// [`ResponseCache`](crate::cache::ResponseCache). `None` means they're
+    /// never cached
+    const CACHE_TTL: Option<Duration> = None;
 
-    type Response;
+    type Response: Serialize + Send + 'static;
+    /// What goes into each request: `()` for static endpoints, [`Uuid`] for
+    /// UUID-keyed ones, etc.
+    type Params: RequestParams + Send + Sync + 'static;
 
-    async fn extract(response: reqwest::Response) -> Result<Self::Response>;
+    /// Builds a request for this endpoint with everything but auth
+    fn build(
+        base: Url,
+        client: &reqwest::Client,
+        params: &Self::Params,
+    ) -> Result<reqwest::RequestBuilder> {
+        let mut base = util::join(&base, Self::PATH)?;
+        let segments = params.path_segments();
+        if !segments.is_empty() {
+            base.path_segments_mut()
+                .map_err(|_| Error::Other("API url is cannot-be-a-base".to_string()))?
+                .extend(&segments);
+        }
+        let query = params.query();
+        if !query.is_empty() {
+            base.query_pairs_mut().extend_pairs(query);
+        }
+        let request = client.request(Self::METHOD, base);
+        Ok(match params.body() {
+            Some(body) => request.json(&body),
+            None => request,
+        })
+    }
+
+    /// Decodes a (fully buffered) response body
+    fn extract(body: &[u8]) -> Result<Self::Response>;
+
+    /// The part of a response body that's actually JSON
+    fn json(body: &[u8]) -> Result<Cow<'_, [u8]>> { Ok(Cow::Borrowed(body)) }
 }
 
 pub trait PublicEndpoint: Endpoint {
-    async fn request(mut base: Url, client: &reqwest::Client) -> Result<reqwest::Response> {
-        base.set_path(Self::PATH);
-        let request = client.request(Self::METHOD, base).build()?;
-        Ok(client.execute(request).await?.error_for_status()?)
+    fn request(
+        base: Url,
+        client: &reqwest::Client,
+        params: &Self::Params,
+    ) -> Result<reqwest::Request> {
+        Ok(Self::build(base, client, params)?.build()?)
     }
 }
 
 pub trait AuthEndpoint: Endpoint {
-    async fn request(
-        mut api: Url,
+    fn request(
+        api: Url,
         client: &reqwest::Client,
         token: &str,
-    ) -> Result<reqwest::Response> {
-        api.set_path(Self::PATH);
-        let request = client
-            .request(Self::METHOD, api)
+        params: &Self::Params,
+    ) -> Result<reqwest::Request> {
+        Ok(Self::build(api, client, params)?
             .bearer_auth(token)
-            .build()?;
-        Ok(client.execute(request).await?.error_for_status()?)
+            .build()?)
     }
 }
 
+/// The per-request parts of an endpoint: extra path segments after
+/// [`Endpoint::PATH`], query parameters and a JSON body
+pub trait RequestParams {
+    fn path_segments(&self) -> Vec<String> { Vec::new() }
+
+    fn query(&self) -> Vec<(String, String)> { Vec::new() }
+
+    fn body(&self) -> Option<serde_json::Value> { None }
+}
+
+/// No parameters at all
+impl RequestParams for () {}
+
+/// `{PATH}/{uuid}`
+impl RequestParams for Uuid {
+    fn path_segments(&self) -> Vec<String> { vec![self.to_string()] }
+}
+
+/// `{PATH}/{id}`
+impl RequestParams for u32 {
+    fn path_segments(&self) -> Vec<String> { vec![self.to_string()] }
+}
+
+/// An endpoint that returns a [`Paged`] list and takes `page`/`limit` query
+/// parameters
+pub trait PagedEndpoint: AuthEndpoint<Response = Paged<Self::Item>> {
+    type Item: Serialize + Send + 'static;
+}
+
 /// ENDPOINT: https://student.naviance.com/rewritten_config.js
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+///
+/// Where every other Naviance service lives. See [`ServiceKind`] and
+/// [`Client::service_url`](crate::Client::service_url)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", default)]
 pub struct Config {
-    pub api_host: Option<String>,
-    pub cpui_url: Option<String>,
-    pub in_product_messaging_url: Option<String>,
-    pub careers2_ui_url: Option<String>,
-    pub hubsui_url: Option<String>,
-    pub eventsui_url: Option<String>,
-    pub scholarshipsui_url: Option<String>,
-    pub naviancescholarshipsui_url: Option<String>,
-    pub naviancesurveysui_url: Option<String>,
-    pub supermatchui_url: Option<String>,
-    pub activematchui_url: Option<String>,
-    pub feedback_url: Option<String>,
+    #[serde(deserialize_with = "url_opt")]
+    pub api_host: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub cpui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub in_product_messaging_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub careers2_ui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub hubsui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub eventsui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub scholarshipsui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub naviancescholarshipsui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub naviancesurveysui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub supermatchui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub activematchui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub feedback_url: Option<Url>,
     pub recaptcha_site_key: Option<String>,
-    pub inline_manual_url: Option<String>,
-    pub common_app_base_url: Option<String>,
+    #[serde(deserialize_with = "url_opt")]
+    pub inline_manual_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub common_app_base_url: Option<Url>,
     pub heap_analytics_api_code: Option<u32>,
-    pub readiness_indicators_ui_url: Option<String>,
+    #[serde(deserialize_with = "url_opt")]
+    pub readiness_indicators_ui_url: Option<Url>,
     pub phrase_batch_limit: Option<u32>,
-    pub headed2_ui_url: Option<String>,
-    pub headed2_token_exchange_url: Option<String>,
-    pub local_opportunities_url: Option<String>,
-    pub headed2_api_domain: Option<String>,
-    pub headed2_app_base_url: Option<String>,
-    pub my_pathways_ui_url: Option<String>,
-    pub my_portfolio_ui_url: Option<String>,
-    pub unified_user_login_url: Option<String>,
-    pub ingest_raw_event_cta_app_base_url: Option<String>,
-    pub portal_api_host: Option<String>,
-    pub appily_match_ui_url: Option<String>,
-    pub appily_match_api_url: Option<String>,
-    pub chatterbox_ui_url: Option<String>,
-    pub ace_chatbot_ui_url: Option<String>,
-    pub ds_api_url: Option<String>,
-    pub ds_api_temp_token: Option<String>,
+    #[serde(deserialize_with = "url_opt")]
+    pub headed2_ui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub headed2_token_exchange_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub local_opportunities_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub headed2_api_domain: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub headed2_app_base_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub my_pathways_ui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub my_portfolio_ui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub unified_user_login_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub ingest_raw_event_cta_app_base_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub portal_api_host: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub appily_match_ui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub appily_match_api_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub chatterbox_ui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub ace_chatbot_ui_url: Option<Url>,
+    #[serde(deserialize_with = "url_opt")]
+    pub ds_api_url: Option<Url>,
+    #[serde(serialize_with = "crate::secret::serialize_exposed_opt")]
+    pub ds_api_temp_token: Option<SecretKey>,
 }
 
 impl Endpoint for Config {
+    type Params = ();
     type Response = Self;
 
     const METHOD: http::Method = http::Method::GET;
     const PATH: &'static str = "/rewritten_config.js";
 
-    async fn extract(response: reqwest::Response) -> Result<Self::Response> {
-        let response = response.text().await?;
-        let response = response
-            .strip_prefix("window.REWRITTEN_CONFIG = ")
-            .ok_or(Error::Other("config.js prefix not found".to_string()))?
-            .strip_suffix(";")
-            .ok_or(Error::Other("config.js suffix not found".to_string()))?;
-        Ok(serde_json::from_str(response)?)
+    fn extract(body: &[u8]) -> Result<Self::Response> {
+        decode_json(Self::PATH, &Self::json(body)?)
+    }
+
+    fn json(body: &[u8]) -> Result<Cow<'_, [u8]>> {
+        let response = std::str::from_utf8(body)
+            .map_err(|e| Error::ConfigParse(format!("config.js is not UTF-8: {e}")))?;
+        Ok(match config_js::extract(response)? {
+            Cow::Borrowed(json) => Cow::Borrowed(json.as_bytes()),
+            Cow::Owned(json) => Cow::Owned(json.into_bytes()),
+        })
     }
 }
 
 impl PublicEndpoint for Config {}
 
+/// The services `rewritten_config.js` tells us about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ServiceKind {
+    /// `API_HOST`, what [`Client`](crate::Client) talks to
+    Api,
+    /// `PORTAL_API_HOST`
+    PortalApi,
+    /// `UNIFIED_USER_LOGIN_URL`
+    Login,
+    /// `CPUI_URL`
+    CollegePlanningUi,
+    InProductMessaging,
+    Careers2Ui,
+    HubsUi,
+    EventsUi,
+    ScholarshipsUi,
+    NavianceScholarshipsUi,
+    NavianceSurveysUi,
+    SuperMatchUi,
+    ActiveMatchUi,
+    Feedback,
+    InlineManual,
+    CommonAppBase,
+    ReadinessIndicatorsUi,
+    Headed2Ui,
+    Headed2TokenExchange,
+    Headed2Api,
+    Headed2AppBase,
+    LocalOpportunities,
+    MyPathwaysUi,
+    MyPortfolioUi,
+    IngestRawEventCtaAppBase,
+    AppilyMatchUi,
+    AppilyMatchApi,
+    ChatterboxUi,
+    AceChatbotUi,
+    /// `DS_API_URL`
+    DsApi,
+}
+
+impl ServiceKind {
+    pub const ALL: [Self; 30] = [
+        Self::Api,
+        Self::PortalApi,
+        Self::Login,
+        Self::CollegePlanningUi,
+        Self::InProductMessaging,
+        Self::Careers2Ui,
+        Self::HubsUi,
+        Self::EventsUi,
+        Self::ScholarshipsUi,
+        Self::NavianceScholarshipsUi,
+        Self::NavianceSurveysUi,
+        Self::SuperMatchUi,
+        Self::ActiveMatchUi,
+        Self::Feedback,
+        Self::InlineManual,
+        Self::CommonAppBase,
+        Self::ReadinessIndicatorsUi,
+        Self::Headed2Ui,
+        Self::Headed2TokenExchange,
+        Self::Headed2Api,
+        Self::Headed2AppBase,
+        Self::LocalOpportunities,
+        Self::MyPathwaysUi,
+        Self::MyPortfolioUi,
+        Self::IngestRawEventCtaAppBase,
+        Self::AppilyMatchUi,
+        Self::AppilyMatchApi,
+        Self::ChatterboxUi,
+        Self::AceChatbotUi,
+        Self::DsApi,
+    ];
+}
+
+impl Config {
+    /// Where `kind` lives, if the config says
+    pub fn service_url(&self, kind: ServiceKind) -> Option<&Url> {
+        use ServiceKind::*;
+        match kind {
+            Api => self.api_host.as_ref(),
+            PortalApi => self.portal_api_host.as_ref(),
+            Login => self.unified_user_login_url.as_ref(),
+            CollegePlanningUi => self.cpui_url.as_ref(),
+            InProductMessaging => self.in_product_messaging_url.as_ref(),
+            Careers2Ui => self.careers2_ui_url.as_ref(),
+            HubsUi => self.hubsui_url.as_ref(),
+            EventsUi => self.eventsui_url.as_ref(),
+            ScholarshipsUi => self.scholarshipsui_url.as_ref(),
+            NavianceScholarshipsUi => self.naviancescholarshipsui_url.as_ref(),
+            NavianceSurveysUi => self.naviancesurveysui_url.as_ref(),
+            SuperMatchUi => self.supermatchui_url.as_ref(),
+            ActiveMatchUi => self.activematchui_url.as_ref(),
+            Feedback => self.feedback_url.as_ref(),
+            InlineManual => self.inline_manual_url.as_ref(),
+            CommonAppBase => self.common_app_base_url.as_ref(),
+            ReadinessIndicatorsUi => self.readiness_indicators_ui_url.as_ref(),
+            Headed2Ui => self.headed2_ui_url.as_ref(),
+            Headed2TokenExchange => self.headed2_token_exchange_url.as_ref(),
+            Headed2Api => self.headed2_api_domain.as_ref(),
+            Headed2AppBase => self.headed2_app_base_url.as_ref(),
+            LocalOpportunities => self.local_opportunities_url.as_ref(),
+            MyPathwaysUi => self.my_pathways_ui_url.as_ref(),
+            MyPortfolioUi => self.my_portfolio_ui_url.as_ref(),
+            IngestRawEventCtaAppBase => self.ingest_raw_event_cta_app_base_url.as_ref(),
+            AppilyMatchUi => self.appily_match_ui_url.as_ref(),
+            AppilyMatchApi => self.appily_match_api_url.as_ref(),
+            ChatterboxUi => self.chatterbox_ui_url.as_ref(),
+            AceChatbotUi => self.ace_chatbot_ui_url.as_ref(),
+            DsApi => self.ds_api_url.as_ref(),
+        }
+    }
+
+    /// Every service the config has a URL for
+    pub fn services(&self) -> impl Iterator<Item = (ServiceKind, &Url)> {
+        ServiceKind::ALL
+            .into_iter()
+            .filter_map(|kind| Some((kind, self.service_url(kind)?)))
+    }
+}
+
+/// A bearer token handed out by the login service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoginToken {
+    #[serde(alias = "accessToken", alias = "access_token", skip_serializing)]
+    pub token: SecretKey,
+}
+
+/// ENDPOINT: {UNIFIED_USER_LOGIN_URL}/authenticate
+///
+/// Trades a username and password for a bearer token
+pub struct PasswordLogin;
+
+/// What [`PasswordLogin`] sends
+#[derive(Debug, Clone)]
+pub struct PasswordLoginParams {
+    pub username: String,
+    pub password: SecretKey,
+    /// The school's Naviance ID, for usernames that aren't unique
+    pub school:   Option<String>,
+}
+
+impl RequestParams for PasswordLoginParams {
+    fn body(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "username": self.username,
+            "password": self.password.expose(),
+            "hsid": self.school,
+        }))
+    }
+}
+
+impl Endpoint for PasswordLogin {
+    type Params = PasswordLoginParams;
+    type Response = LoginToken;
+
+    const METHOD: http::Method = http::Method::POST;
+    const PATH: &'static str = "/authenticate";
+
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
+}
+
+impl PublicEndpoint for PasswordLogin {}
+
+/// ENDPOINT: {UNIFIED_USER_LOGIN_URL}/sso/token
+///
+/// Trades the code an SSO provider redirected back with for a bearer token
+pub struct SsoLogin;
+
+/// What [`SsoLogin`] sends
+#[derive(Debug, Clone)]
+pub struct SsoLoginParams {
+    pub code:         SecretKey,
+    /// Has to match the one the login started with
+    pub redirect_uri: Url,
+}
+
+impl RequestParams for SsoLoginParams {
+    fn body(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "code": self.code.expose(),
+            "redirectUri": self.redirect_uri,
+        }))
+    }
+}
+
+impl Endpoint for SsoLogin {
+    type Params = SsoLoginParams;
+    type Response = LoginToken;
+
+    const METHOD: http::Method = http::Method::POST;
+    const PATH: &'static str = "/sso/token";
+
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
+}
+
+impl PublicEndpoint for SsoLogin {}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Paged<T> {
@@ -120,18 +538,119 @@ pub struct Paged<T> {
 pub type SchoolsImThinkingAbout = Paged<School>;
 
 impl Endpoint for SchoolsImThinkingAbout {
+    type Params = ();
     type Response = Self;
 
     const METHOD: http::Method = http::Method::GET;
     const PATH: &'static str = "/college/colleges-im-thinking-about";
 
-    async fn extract(response: reqwest::Response) -> Result<Self::Response> {
-        Ok(response.json().await?)
-    }
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
 }
 
 impl AuthEndpoint for SchoolsImThinkingAbout {}
 
+impl PagedEndpoint for SchoolsImThinkingAbout {
+    type Item = School;
+}
+
+/// ENDPOINT: POST https://api.naviance.com/college/colleges-im-thinking-about
+///
+/// Puts a college on your personal list
+pub struct AddSchoolImThinkingAbout;
+
+/// What [`AddSchoolImThinkingAbout`] sends
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSchool {
+    pub college_uuid:     Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interest_level:   Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_outcome: Option<u32>,
+}
+
+impl NewSchool {
+    pub fn new(college_uuid: Uuid) -> Self {
+        Self {
+            college_uuid,
+            interest_level: None,
+            expected_outcome: None,
+        }
+    }
+}
+
+impl RequestParams for NewSchool {
+    fn body(&self) -> Option<serde_json::Value> { serde_json::to_value(self).ok() }
+}
+
+impl Endpoint for AddSchoolImThinkingAbout {
+    type Params = NewSchool;
+    type Response = School;
+
+    const METHOD: http::Method = http::Method::POST;
+    const PATH: &'static str = "/college/colleges-im-thinking-about";
+
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
+}
+
+impl AuthEndpoint for AddSchoolImThinkingAbout {}
+
+/// ENDPOINT: PATCH https://api.naviance.com/college/colleges-im-thinking-about/{id}
+///
+/// Changes how interested you are in a college on your list, and what you
+/// expect to happen
+pub struct UpdateSchoolImThinkingAbout;
+
+/// What [`UpdateSchoolImThinkingAbout`] sends. Fields left as `None` stay as
+/// they are
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchoolUpdate {
+    /// [`School::id`], not the college's
+    #[serde(skip)]
+    pub id:               u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interest_level:   Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_outcome: Option<u32>,
+}
+
+impl RequestParams for SchoolUpdate {
+    fn path_segments(&self) -> Vec<String> { vec![self.id.to_string()] }
+
+    fn body(&self) -> Option<serde_json::Value> { serde_json::to_value(self).ok() }
+}
+
+impl Endpoint for UpdateSchoolImThinkingAbout {
+    type Params = SchoolUpdate;
+    type Response = School;
+
+    const METHOD: http::Method = http::Method::PATCH;
+    const PATH: &'static str = "/college/colleges-im-thinking-about";
+
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
+}
+
+impl AuthEndpoint for UpdateSchoolImThinkingAbout {}
+
+/// ENDPOINT: DELETE https://api.naviance.com/college/colleges-im-thinking-about/{id}
+///
+/// Takes a college off your list. Takes [`School::id`]
+pub struct RemoveSchoolImThinkingAbout;
+
+impl Endpoint for RemoveSchoolImThinkingAbout {
+    type Params = u32;
+    type Response = ();
+
+    const METHOD: http::Method = http::Method::DELETE;
+    const PATH: &'static str = "/college/colleges-im-thinking-about";
+
+    // Nothing worth reading comes back
+    fn extract(_body: &[u8]) -> Result<Self::Response> { Ok(()) }
+}
+
+impl AuthEndpoint for RemoveSchoolImThinkingAbout {}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct School {
@@ -176,12 +695,15 @@ pub struct College {
     pub latitude: Option<f64>,
     #[serde(deserialize_with = "none_if_empty_string")]
     pub url: Option<String>,
+    /// [`Sector::code`]
     pub sector: Option<u32>,
+    /// [`CollegeSize::code`]
+    pub size: Option<u32>,
     #[serde(deserialize_with = "none_if_empty_string")]
     pub admissions_email: Option<String>,
     #[serde(deserialize_with = "none_if_empty_string")]
     pub intl_admissions_email: Option<String>,
-    // pub hobsons_ext_profile: Option<serde_json::Value>,
+    pub hobsons_ext_profile: Option<serde_json::Value>,
     #[serde(deserialize_with = "bool_from_int_opt")]
     pub ssr_required: Option<bool>,
     #[serde(deserialize_with = "bool_from_int_opt")]
@@ -194,7 +716,7 @@ pub struct College {
     pub is_college_active: Option<bool>,
     #[serde(deserialize_with = "none_if_empty_string")]
     pub parchment_id: Option<String>,
-    // pub scribbles_id: Option<serde_json::Value>,
+    pub scribbles_id: Option<serde_json::Value>,
     pub edocs_college: Option<EdocsCollege>,
     pub school_area: Option<SchoolArea>,
     pub core_mapping: Option<CoreMapping>,
@@ -205,54 +727,281 @@ pub struct College {
 }
 
 impl Endpoint for College {
+    type Params = Uuid;
     type Response = Self;
 
+    // Profiles (addresses, deadlines, test policy) get edited a few times a year
+    const CACHE_TTL: Option<Duration> = Some(Duration::from_secs(30 * 24 * 60 * 60));
     const METHOD: http::Method = http::Method::GET;
     const PATH: &'static str = "/college/uuid";
 
-    async fn extract(response: reqwest::Response) -> Result<Self::Response> {
-        Ok(response.json().await?)
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
+}
+
+impl AuthEndpoint for College {}
+
+/// ENDPOINT: https://api.naviance.com/college/search
+///
+/// Every college matching a [`CollegeQuery`], a page at a time
+pub struct CollegeSearch;
+
+impl Endpoint for CollegeSearch {
+    type Params = CollegeQuery;
+    type Response = Paged<College>;
+
+    const METHOD: http::Method = http::Method::GET;
+    const PATH: &'static str = "/college/search";
+
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
+}
+
+impl AuthEndpoint for CollegeSearch {}
+
+impl PagedEndpoint for CollegeSearch {
+    type Item = College;
+}
+
+/// Filters for [`CollegeSearch`]. A college has to match all of them, and
+/// any one of several states, sectors or sizes
+///
+/// ```
+/// use naviance::types::{CollegeQuery, CollegeSize, Sector, TestPolicy};
+///
+/// let query = CollegeQuery::new()
+///     .name("state")
+///     .state("MI")
+///     .state("OH")
+///     .sector(Sector::Public4Year)
+///     .size(CollegeSize::Large)
+///     .size(CollegeSize::VeryLarge)
+///     .test_policy(TestPolicy::Optional);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CollegeQuery {
+    pub name:        Option<String>,
+    pub states:      Vec<String>,
+    pub country:     Option<String>,
+    pub sectors:     Vec<Sector>,
+    pub sizes:       Vec<CollegeSize>,
+    /// Miles from a ZIP code
+    pub within:      Option<(u32, String)>,
+    pub test_policy: Option<TestPolicy>,
+}
+
+impl CollegeQuery {
+    pub fn new() -> Self { Self::default() }
+
+    /// Anywhere in the name, case-insensitive
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Two-letter code, e.g. `MI`. Can be given more than once
+    pub fn state(mut self, state: impl Into<String>) -> Self {
+        self.states.push(state.into());
+        self
+    }
+
+    /// Two-letter code, e.g. `US`
+    pub fn country(mut self, country: impl Into<String>) -> Self {
+        self.country = Some(country.into());
+        self
+    }
+
+    /// Can be given more than once
+    pub fn sector(mut self, sector: Sector) -> Self {
+        self.sectors.push(sector);
+        self
+    }
+
+    /// Can be given more than once
+    pub fn size(mut self, size: CollegeSize) -> Self {
+        self.sizes.push(size);
+        self
+    }
+
+    pub fn within_miles(mut self, miles: u32, zip: impl Into<String>) -> Self {
+        self.within = Some((miles, zip.into()));
+        self
+    }
+
+    pub fn test_policy(mut self, test_policy: TestPolicy) -> Self {
+        self.test_policy = Some(test_policy);
+        self
     }
 }
 
-impl College {
-    pub async fn request(
-        mut api: Url,
-        client: &reqwest::Client,
-        token: &str,
-        uuid: &Uuid,
-    ) -> Result<reqwest::Response> {
-        api.set_path(Self::PATH);
-        api.path_segments_mut()
-            .map_err(|_| Error::Other("API url is cannot-be-a-base".to_string()))?
-            .push(&uuid.to_string());
-        let request = client
-            .request(Self::METHOD, api)
-            .bearer_auth(token)
-            .build()?;
-        Ok(client.execute(request).await?.error_for_status()?)
+impl RequestParams for CollegeQuery {
+    fn query(&self) -> Vec<(String, String)> {
+        let mut query = Vec::new();
+        let mut push = |name: &str, value: String| query.push((name.to_string(), value));
+        if let Some(name) = &self.name {
+            push("name", name.clone());
+        }
+        for state in &self.states {
+            push("state", state.clone());
+        }
+        if let Some(country) = &self.country {
+            push("country", country.clone());
+        }
+        for sector in &self.sectors {
+            push("sector", sector.code().to_string());
+        }
+        for size in &self.sizes {
+            push("size", size.code().to_string());
+        }
+        if let Some((miles, zip)) = &self.within {
+            push("distance", miles.to_string());
+            push("zip", zip.clone());
+        }
+        if let Some(test_policy) = self.test_policy {
+            push("testPolicy", test_policy.as_str().to_string());
+        }
+        query
+    }
+}
+
+coded_enum! {
+    /// IPEDS sectors, as in [`College::sector`]
+    pub enum Sector {
+        Public4Year = 1,
+        PrivateNonprofit4Year = 2,
+        PrivateForProfit4Year = 3,
+        Public2Year = 4,
+        PrivateNonprofit2Year = 5,
+        PrivateForProfit2Year = 6,
     }
+}
+
+coded_enum! {
+    /// IPEDS size categories by total enrollment, as in [`College::size`]
+    pub enum CollegeSize {
+        /// Under 1,000 students
+        VerySmall = 1,
+        /// 1,000 to 4,999
+        Small = 2,
+        /// 5,000 to 9,999
+        Medium = 3,
+        /// 10,000 to 19,999
+        Large = 4,
+        /// 20,000 and up
+        VeryLarge = 5,
+    }
+}
+
+/// Whether a college wants SAT/ACT scores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TestPolicy {
+    Required,
+    Optional,
+    /// Takes other tests (AP, IB, ...) in place of the SAT/ACT
+    Flexible,
+    /// Ignores scores even if you send them
+    Blind,
+}
+
+impl TestPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Required => "REQUIRED",
+            Self::Optional => "OPTIONAL",
+            Self::Flexible => "FLEXIBLE",
+            Self::Blind => "BLIND",
+        }
+    }
+}
+
+pub type CollegesImApplyingTo = Paged<Application>;
 
-    // pub async fn extract(response: reqwest::Response) -> Result<Self> {
-    // Ok(response.json().await?) }
-}
-
-// impl AuthEndpoint for College {
-//     async fn request(
-//         mut api: Url,
-//         client: &reqwest::Client,
-//         uuid: &str,
-//         token: &str,
-//     ) -> Result<reqwest::Response> {
-//         api.set_path(Self::PATH);
-//         api.path_segments_mut()?.push(uuid);
-//         let request = client
-//             .request(Self::METHOD, api)
-//             .bearer_auth(token)
-//             .build()?;
-//         Ok(client.execute(request).await?.error_for_status()?)
-//     }
-// }
+impl Endpoint for CollegesImApplyingTo {
+    type Params = ();
+    type Response = Self;
+
+    const METHOD: http::Method = http::Method::GET;
+    const PATH: &'static str = "/college/colleges-im-applying-to";
+
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
+}
+
+impl AuthEndpoint for CollegesImApplyingTo {}
+
+impl PagedEndpoint for CollegesImApplyingTo {
+    type Item = Application;
+}
+
+/// One college on the applying list. The list leaves out
+/// `recommendations`, fetch the application by [`Application::id`] for those
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Application {
+    pub id:                Option<u32>,
+    #[serde(deserialize_with = "none_if_empty_string")]
+    pub college_id:        Option<String>,
+    pub college:           Option<College>,
+    /// ED, EA, RD, ...
+    pub application_type:  Option<TypeName>,
+    pub submission_status: Option<SubmissionStatus>,
+    pub submitted_date:    Option<NaiveDate>,
+    pub deadline:          Option<NaiveDate>,
+    pub transcript_status: Option<DocumentStatus>,
+    pub recommendations:   Option<Vec<Recommendation>>,
+    pub decision:          Option<Decision>,
+    pub decision_date:     Option<NaiveDate>,
+    pub date_added:        Option<NaiveDate>,
+}
+
+impl Endpoint for Application {
+    type Params = u32;
+    type Response = Self;
+
+    const METHOD: http::Method = http::Method::GET;
+    const PATH: &'static str = "/application";
+
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
+}
+
+impl AuthEndpoint for Application {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub id:             Option<u32>,
+    #[serde(deserialize_with = "none_if_empty_string")]
+    pub teacher_name:   Option<String>,
+    pub status:         Option<DocumentStatus>,
+    pub requested_date: Option<NaiveDate>,
+    pub submitted_date: Option<NaiveDate>,
+}
+
+lenient_enum! {
+    pub enum SubmissionStatus {
+        NotStarted = "NOT_STARTED",
+        InProgress = "IN_PROGRESS",
+        Submitted = "SUBMITTED",
+    }
+}
+
+lenient_enum! {
+    /// Where a transcript or recommendation letter is at
+    pub enum DocumentStatus {
+        NotRequested = "NOT_REQUESTED",
+        Requested = "REQUESTED",
+        InProgress = "IN_PROGRESS",
+        Submitted = "SUBMITTED",
+    }
+}
+
+lenient_enum! {
+    pub enum Decision {
+        Pending = "PENDING",
+        Accepted = "ACCEPTED",
+        Denied = "DENIED",
+        Deferred = "DEFERRED",
+        Waitlisted = "WAITLISTED",
+        Withdrawn = "WITHDRAWN",
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CoreMapping {
@@ -308,14 +1057,13 @@ pub struct SchoolArea {
 pub type ScattergramSources = Vec<ScattergramSource>;
 
 impl Endpoint for ScattergramSources {
+    type Params = ();
     type Response = Self;
 
     const METHOD: http::Method = http::Method::GET;
     const PATH: &'static str = "/college/scattergram";
 
-    async fn extract(response: reqwest::Response) -> Result<Self::Response> {
-        Ok(response.json().await?)
-    }
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
 }
 
 impl AuthEndpoint for ScattergramSources {}
@@ -334,42 +1082,26 @@ pub struct ScattergramSource {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ApplicationStatistics {
-    pub scattergrams: Option<Scattergrams>,
-    // pub application_statistics: Option<serde_json::Value>,
-    // pub applications_by_year:   Option<serde_json::Value>,
-    pub user_info:    Option<UserInfo>,
-    // pub peer_gpa_map:           Option<Vec<Option<serde_json::Value>>>,
+    pub scattergrams:           Option<Scattergrams>,
+    pub application_statistics: Option<serde_json::Value>,
+    pub applications_by_year:   Option<serde_json::Value>,
+    pub user_info:              Option<UserInfo>,
+    pub peer_gpa_map:           Option<Vec<Option<serde_json::Value>>>,
 }
 
 impl Endpoint for ApplicationStatistics {
+    type Params = Uuid;
     type Response = Self;
 
+    // Scattergrams are only rebuilt once a class's admissions results are in
+    const CACHE_TTL: Option<Duration> = Some(Duration::from_secs(30 * 24 * 60 * 60));
     const METHOD: http::Method = http::Method::GET;
     const PATH: &'static str = "/application-statistics/uuid";
 
-    async fn extract(response: reqwest::Response) -> Result<Self::Response> {
-        Ok(response.json().await?)
-    }
+    fn extract(body: &[u8]) -> Result<Self::Response> { decode_json(Self::PATH, body) }
 }
 
-impl ApplicationStatistics {
-    pub async fn request(
-        mut api: Url,
-        client: &reqwest::Client,
-        token: &str,
-        uuid: &Uuid,
-    ) -> Result<reqwest::Response> {
-        api.set_path(Self::PATH);
-        api.path_segments_mut()
-            .map_err(|_| Error::Other("API url is cannot-be-a-base".to_string()))?
-            .push(&uuid.to_string());
-        let request = client
-            .request(Self::METHOD, api)
-            .bearer_auth(token)
-            .build()?;
-        Ok(client.execute(request).await?.error_for_status()?)
-    }
-}
+impl AuthEndpoint for ApplicationStatistics {}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -476,7 +1208,7 @@ pub struct App<T: TestType> {
     #[serde(rename = "studentSAT1600Composite")]
     #[serde(deserialize_with = "none_if_zero")]
     pub student_sat1600_composite: Option<u32>,
-    // pub is_test_optional: Option<serde_json::Value>,
+    pub is_test_optional: Option<serde_json::Value>,
     pub gpa: Option<f64>,
     #[serde(skip)]
     _marker: std::marker::PhantomData<T>,
@@ -486,30 +1218,29 @@ impl App<SAT> {
     pub fn to_act(&self) -> App<ACT> {
         App {
             current_student: self.current_student,
-            type_name: self.type_name.clone(),
-            act_composite: self.highest_combo_sat.map(|sat| sat_to_act(sat)),
+            type_name: self.type_name,
+            act_composite: self.highest_combo_sat.map(sat_to_act),
             act_composite_student: None,
             highest_combo_sat: self.highest_combo_sat,
             student_sat1600_composite: self.student_sat1600_composite,
+            is_test_optional: self.is_test_optional.clone(),
             gpa: self.gpa,
             _marker: std::marker::PhantomData,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Hash, Eq)]
-pub enum TypeName {
-    REA,
-    EA,
-    EA2,
-    ED,
-    ED2,
-    RD,
-    ROLL,
-    #[serde(rename = "OTH")]
-    OTH,
-    #[serde(other)]
-    Unknown,
+lenient_enum! {
+    pub enum TypeName {
+        REA = "REA",
+        EA = "EA",
+        EA2 = "EA2",
+        ED = "ED",
+        ED2 = "ED2",
+        RD = "RD",
+        ROLL = "ROLL",
+        OTH = "OTH",
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1,9 +1,23 @@
-use std::ops::RangeInclusive;
+use std::{
+    cell::Cell,
+    ops::RangeInclusive,
+    path::{Path as StdPath, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
 
 use serde::{
-    de::{self, Unexpected},
+    de::{self, DeserializeOwned, Unexpected},
     Deserialize, Deserializer,
 };
+use serde_json::Value;
+use serde_path_to_error::{Path, Segment};
+use tracing::warn;
+use url::Url;
+
+use crate::{DecodeError, Error};
+
+/// How much of a response body makes it into error messages
+const SNIPPET_LEN: usize = 200;
 
 pub fn sat_to_act(mut sat: u32) -> u32 {
     sat /= 10;
@@ -113,6 +127,7 @@ where
 //     }
 // }
 
+/// `0`/`1` to a bool. Anything else is an error, strict or not
 pub fn bool_from_int_opt<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
 where
     D: Deserializer<'de>,
@@ -127,3 +142,127 @@ where
         )),
     }
 }
+
+/// A service URL from `rewritten_config.js`. Empty means unset, and bare
+/// hosts get `https://`. Anything unparseable is an error in strict mode and
+/// `None` otherwise
+pub fn url_opt<'de, D>(deserializer: D) -> Result<Option<Url>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(raw) = none_if_empty_string(deserializer)? else {
+        return Ok(None);
+    };
+    let parsed = match Url::parse(&raw) {
+        Err(url::ParseError::RelativeUrlWithoutBase) => Url::parse(&format!("https://{raw}")),
+        parsed => parsed,
+    };
+    match parsed {
+        Ok(url) => Ok(Some(url)),
+        Err(e) if is_strict() => Err(de::Error::custom(format!("invalid URL {raw:?}: {e}"))),
+        Err(e) => {
+            warn!("Ignoring invalid URL {raw:?} in config: {e}");
+            Ok(None)
+        }
+    }
+}
+
+thread_local! {
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether the response currently being decoded should be decoded strictly
+///
+/// Only meaningful inside a [`Deserialize`] impl
+pub fn is_strict() -> bool { STRICT.with(Cell::get) }
+
+/// Runs `f` (which should decode something, synchronously) in strict or
+/// lenient mode
+pub(crate) fn with_strictness<R>(strict: bool, f: impl FnOnce() -> R) -> R {
+    let previous = STRICT.with(|s| s.replace(strict));
+    let result = f();
+    STRICT.with(|s| s.set(previous));
+    result
+}
+
+/// A short, printable excerpt of a response body for error messages
+pub(crate) fn snippet(body: &[u8]) -> String {
+    let body = String::from_utf8_lossy(body);
+    let body = body.trim();
+    match body.char_indices().nth(SNIPPET_LEN) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+/// Deserializes a JSON response body from `endpoint`, keeping track of where
+/// exactly it failed
+pub(crate) fn decode_json<T: DeserializeOwned>(endpoint: &str, body: &[u8]) -> crate::Result<T> {
+    let de = &mut serde_json::Deserializer::from_slice(body);
+    serde_path_to_error::deserialize(de).map_err(|e| {
+        let value_snippet = serde_json::from_slice::<Value>(body)
+            .ok()
+            .and_then(|root| value_at(&root, e.path()).map(|v| snippet(v.to_string().as_bytes())));
+        let source = e.inner();
+        let expected = source
+            .to_string()
+            .split_once(", expected ")
+            .map(|(_, expected)| {
+                // serde_json tacks the position on at the end
+                match expected.rsplit_once(" at line ") {
+                    Some((expected, _)) => expected.to_string(),
+                    None => expected.to_string(),
+                }
+            });
+        Error::from(DecodeError {
+            endpoint: endpoint.to_string(),
+            json_path: e.path().to_string(),
+            expected,
+            value_snippet,
+            body_snippet: snippet(body),
+            dump: None,
+            source: e.into_inner(),
+        })
+    })
+}
+
+/// Locks `mutex`, carrying on if another thread panicked while holding it
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `path` under `base`, keeping whatever path `base` already has, so
+/// `https://example.com/login` + `/sso` is `https://example.com/login/sso`
+pub(crate) fn join(base: &Url, path: &str) -> Result<Url, Error> {
+    let mut base = base.clone();
+    if !base.path().ends_with('/') {
+        let dir = format!("{}/", base.path());
+        base.set_path(&dir);
+    }
+    Ok(base.join(path.trim_start_matches('/'))?)
+}
+
+/// Follows a serde path through an already parsed body
+fn value_at<'a>(root: &'a Value, path: &Path) -> Option<&'a Value> {
+    path.iter().try_fold(root, |value, segment| match segment {
+        Segment::Seq { index } => value.get(index),
+        Segment::Map { key } => value.get(key),
+        Segment::Enum { .. } => Some(value),
+        Segment::Unknown => None,
+    })
+}
+
+/// Writes a body that failed to decode to `dir` for later inspection
+pub(crate) fn dump_body(dir: &StdPath, endpoint: &str, body: &[u8]) -> Option<PathBuf> {
+    let name = endpoint.trim_matches('/').replace('/', "_");
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3f");
+    let path = dir.join(format!("{name}-{stamp}.json"));
+    let written = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, body));
+    match written {
+        Ok(()) => Some(path),
+        Err(e) => {
+            warn!("Failed to dump body to {}: {e}", path.display());
+            None
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! The colleges a student is applying to, and where each application is at
+#![cfg(feature = "testing")]
+
+use chrono::NaiveDate;
+use naviance::{
+    testing::MockServer,
+    types::{Decision, DocumentStatus, SubmissionStatus, TypeName},
+};
+
+#[tokio::test]
+async fn applying_to_list() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let page = client.get_colleges_im_applying_to().await.unwrap();
+    assert_eq!(page.total_items, Some(3));
+
+    let applications = client.get_all_colleges_im_applying_to().await.unwrap();
+    let summary: Vec<_> = applications
+        .iter()
+        .map(|a| {
+            (
+                a.id.unwrap(),
+                a.application_type.unwrap(),
+                a.submission_status.unwrap(),
+                a.transcript_status.unwrap(),
+                a.decision,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (
+                201,
+                TypeName::EA,
+                SubmissionStatus::Submitted,
+                DocumentStatus::Submitted,
+                Some(Decision::Accepted)
+            ),
+            (
+                202,
+                TypeName::ED,
+                SubmissionStatus::Submitted,
+                DocumentStatus::Requested,
+                Some(Decision::Pending)
+            ),
+            (
+                203,
+                TypeName::RD,
+                SubmissionStatus::InProgress,
+                DocumentStatus::NotRequested,
+                None
+            ),
+        ]
+    );
+    let purdue = &applications[2];
+    assert_eq!(purdue.deadline, NaiveDate::from_ymd_opt(2025, 1, 15));
+    assert_eq!(
+        purdue.college.as_ref().unwrap().name.as_deref(),
+        Some("Purdue University")
+    );
+}
+
+#[tokio::test]
+async fn application_detail() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let application = client.get_application(202).await.unwrap();
+    assert_eq!(application.id, Some(202));
+    assert_eq!(application.decision, Some(Decision::Pending));
+    let recommendations: Vec<_> = application
+        .recommendations
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.teacher_name.unwrap(), r.status.unwrap(), r.submitted_date))
+        .collect();
+    assert_eq!(
+        recommendations,
+        [
+            (
+                "Ms. Rivera".to_string(),
+                DocumentStatus::Submitted,
+                NaiveDate::from_ymd_opt(2024, 10, 2)
+            ),
+            ("Mr. Chen".to_string(), DocumentStatus::Requested, None),
+        ]
+    );
+    assert_eq!(server.requests().pop().unwrap().path, "/application/202");
+}
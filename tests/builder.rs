@@ -0,0 +1,71 @@
+//! Building a client: where the API base comes from, and overriding it
+#![cfg(feature = "testing")]
+
+mod common;
+
+use naviance::{
+    testing::MockServer,
+    types::{Config, Endpoint, ScattergramSources},
+    Client, Error,
+};
+
+#[tokio::test]
+async fn api_base_comes_from_the_config() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    assert_eq!(client.api_base(), &server.url());
+    assert_eq!(server.requests()[0].path, Config::PATH);
+    assert!(client.config().is_some());
+}
+
+#[tokio::test]
+async fn api_base_skips_the_config() {
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .api_base(server.url())
+        .build()
+        .await
+        .unwrap();
+    assert!(client.config().is_none());
+    assert!(server.requests().is_empty());
+    client.get_scattergram_sources().await.unwrap();
+}
+
+#[tokio::test]
+async fn endpoint_paths_are_joined_onto_the_api_base() {
+    let server = MockServer::start().await;
+    for base in ["api", "api/"] {
+        let client = server
+            .client_builder()
+            .api_base(server.url().join(base).unwrap())
+            .build()
+            .await
+            .unwrap();
+        // Nothing lives there, but it has to be asked for
+        let result = client.get_scattergram_sources().await;
+        assert!(matches!(result, Err(Error::NotFound { .. })), "{result:?}");
+        let last = server.requests().pop().unwrap();
+        assert_eq!(last.path, format!("/api{}", ScattergramSources::PATH));
+    }
+}
+
+#[tokio::test]
+async fn a_key_is_required() {
+    let server = MockServer::start().await;
+    let result = common::keyless(&server).build().await;
+    assert!(matches!(result, Err(Error::Other(_))), "{result:?}");
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn https_only_by_default() {
+    let server = MockServer::start().await;
+    let result = Client::builder()
+        .key("key")
+        .student_base(server.url())
+        .build()
+        .await;
+    assert!(matches!(result, Err(Error::Http(_))), "{result:?}");
+    assert!(server.requests().is_empty());
+}
@@ -0,0 +1,295 @@
+//! The on-disk response cache: TTLs, modes, purging and keeping students'
+//! entries apart
+#![cfg(feature = "testing")]
+
+mod common;
+
+use std::{path::Path, time::Duration};
+
+use common::{count, jwt, temp_path, MICHIGAN, PURDUE};
+use naviance::{
+    cache::{CacheMode, ResponseCache},
+    testing::MockServer,
+    types::{Application, ApplicationStatistics, College, Endpoint, ScattergramSources},
+    Error,
+};
+use serde_json::{json, Value};
+
+/// A key for `sub`, issued at `iat`
+fn student_key(sub: &str, iat: i64) -> String {
+    jwt(&format!(
+        r#"{{"sub":"{sub}","iat":{iat},"exp":4102444800}}"#
+    ))
+}
+
+/// How many entries are cached in `dir`
+fn files(dir: &Path) -> usize { std::fs::read_dir(dir).unwrap().count() }
+
+#[tokio::test]
+async fn fresh_entries_are_served_from_disk() {
+    let dir = temp_path("cache-fresh");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir))
+        .build()
+        .await
+        .unwrap();
+    let first = client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    let second = client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(first, second);
+    assert_eq!(count(&server, College::PATH), 1);
+
+    // A new client with the same directory picks them up
+    let client = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir))
+        .build()
+        .await
+        .unwrap();
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(count(&server, College::PATH), 1);
+
+    // Only endpoints with a TTL are cached
+    client.get_scattergram_sources().await.unwrap();
+    client.get_scattergram_sources().await.unwrap();
+    assert_eq!(count(&server, ScattergramSources::PATH), 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn ttls_can_be_overridden() {
+    let dir = temp_path("cache-ttl");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .cache(
+            ResponseCache::new(&dir)
+                .ttl::<College>(Some(Duration::ZERO))
+                .ttl::<ScattergramSources>(Some(Duration::from_secs(60))),
+        )
+        .build()
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+        client.get_scattergram_sources().await.unwrap();
+    }
+    assert_eq!(count(&server, College::PATH), 2);
+    assert_eq!(count(&server, ScattergramSources::PATH), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn cache_only_and_refresh() {
+    let dir = temp_path("cache-modes");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir))
+        .build()
+        .await
+        .unwrap();
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+
+    let offline = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir).mode(CacheMode::CacheOnly))
+        .build()
+        .await
+        .unwrap();
+    offline.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    match offline.get_college_info_by_uuid(&PURDUE).await {
+        Err(Error::NotCached { endpoint, url }) => {
+            assert_eq!(endpoint, College::PATH);
+            assert!(url.ends_with(&PURDUE.to_string()), "{url}");
+        }
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(count(&server, College::PATH), 1);
+
+    let refresh = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir).mode(CacheMode::Refresh))
+        .build()
+        .await
+        .unwrap();
+    refresh.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(count(&server, College::PATH), 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn entries_are_kept_per_student() {
+    let dir = temp_path("cache-identity");
+    let server = MockServer::start_with_key(student_key("student-1", 1)).await;
+    let cached = |key: String| {
+        server
+            .client_builder()
+            .key(key)
+            .cache(ResponseCache::new(&dir).mode(CacheMode::CacheOnly))
+    };
+    let client = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir))
+        .build()
+        .await
+        .unwrap();
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+
+    // A new token for the same student still hits
+    let renewed = cached(student_key("student-1", 2)).build().await.unwrap();
+    renewed.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+
+    for key in [student_key("student-2", 1), "opaque-key".to_string()] {
+        let other = cached(key).build().await.unwrap();
+        let result = other.get_college_info_by_uuid(&MICHIGAN).await;
+        assert!(matches!(result, Err(Error::NotCached { .. })), "{result:?}");
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn purge() {
+    let dir = temp_path("cache-purge");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir))
+        .build()
+        .await
+        .unwrap();
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    client
+        .get_application_stats_by_uuid(&MICHIGAN)
+        .await
+        .unwrap();
+    assert_eq!(files(&dir), 2);
+
+    let cache = client.cache().unwrap();
+    cache.purge_endpoint::<College>().unwrap();
+    assert_eq!(files(&dir), 1);
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(count(&server, College::PATH), 2);
+
+    cache.purge().unwrap();
+    assert_eq!(files(&dir), 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn purging_leaves_endpoints_with_a_longer_path_alone() {
+    let dir = temp_path("cache-purge-prefix");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir).ttl::<Application>(Some(Duration::from_secs(60))))
+        .build()
+        .await
+        .unwrap();
+    client.get_application(202).await.unwrap();
+    client
+        .get_application_stats_by_uuid(&MICHIGAN)
+        .await
+        .unwrap();
+    assert_eq!(files(&dir), 2);
+
+    // `/application` is a prefix of `/application-statistics/uuid`
+    client
+        .cache()
+        .unwrap()
+        .purge_endpoint::<Application>()
+        .unwrap();
+    assert_eq!(files(&dir), 1);
+    client
+        .get_application_stats_by_uuid(&MICHIGAN)
+        .await
+        .unwrap();
+    assert_eq!(count(&server, ApplicationStatistics::PATH), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn purging_works_under_an_api_base_with_a_path() {
+    let dir = temp_path("cache-purge-base");
+    let server = MockServer::start().await;
+    let prefixed = format!("/api{}", College::PATH);
+    server.set_fixture(&prefixed, include_str!("../fixtures/college.json"));
+    let client = server
+        .client_builder()
+        .api_base(server.url().join("api/").unwrap())
+        .cache(ResponseCache::new(&dir))
+        .build()
+        .await
+        .unwrap();
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(files(&dir), 1);
+    client.cache().unwrap().purge_endpoint::<College>().unwrap();
+    assert_eq!(files(&dir), 0);
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(count(&server, &prefixed), 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn stale_entries_are_revalidated() {
+    let dir = temp_path("cache-revalidate");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir).ttl::<College>(Some(Duration::ZERO)))
+        .build()
+        .await
+        .unwrap();
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+
+    // Tamper with the entry, so it's clear where the next answer comes from
+    let entry = std::fs::read_dir(&dir)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let mut cached: Value = serde_json::from_slice(&std::fs::read(&entry).unwrap()).unwrap();
+    assert!(cached["etag"].is_string(), "{cached}");
+    let body = common::fixture(cached["body"].as_str().unwrap(), |body| {
+        body["name"] = json!("From the cache")
+    });
+    cached["body"] = json!(body);
+    std::fs::write(&entry, cached.to_string()).unwrap();
+
+    // Still the same on the server, so it says 304
+    let college = client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(college.name.as_deref(), Some("From the cache"));
+    assert_eq!(count(&server, College::PATH), 2);
+
+    // Until it isn't
+    server.set_fixture(
+        College::PATH,
+        common::fixture(include_str!("../fixtures/college.json"), |body| {
+            body["name"] = json!("From the server")
+        }),
+    );
+    let college = client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(college.name.as_deref(), Some("From the server"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn undecodable_entries_are_dropped() {
+    let dir = temp_path("cache-undecodable");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .cache(ResponseCache::new(&dir))
+        .build()
+        .await
+        .unwrap();
+    server.set_fixture(College::PATH, r#"{"latitude":"north"}"#);
+    let result = client.get_college_info_by_uuid(&MICHIGAN).await;
+    assert!(matches!(result, Err(Error::Decode(_))), "{result:?}");
+    server.set_fixture(College::PATH, include_str!("../fixtures/college.json"));
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(count(&server, College::PATH), 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,118 @@
+//! Recording a session against the mock server and replaying it without one
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{temp_path, MICHIGAN};
+use naviance::{
+    cassette::Cassette,
+    testing::{MockServer, MOCK_KEY},
+    types::{Config, Endpoint, SchoolsImThinkingAbout},
+    Client, Error,
+};
+
+#[tokio::test]
+async fn record_then_replay() {
+    let path = temp_path("replay.json");
+    let server = MockServer::start().await;
+    let client = server.client_builder().record(&path).build().await.unwrap();
+    let schools = client.get_all_schools_im_thinking_about().await.unwrap();
+    let sources = client.get_scattergram_sources().await.unwrap();
+    client.save_cassette().await.unwrap();
+    drop(server);
+
+    let recorded = std::fs::read_to_string(&path).unwrap();
+    assert!(!recorded.contains(MOCK_KEY));
+    let cassette = Cassette::load(&path).unwrap();
+    assert_eq!(cassette.interactions.len(), 3);
+    assert_eq!(
+        cassette.interactions[1].url.path(),
+        SchoolsImThinkingAbout::PATH
+    );
+
+    // Nothing is listening any more, and any key will do
+    let replay = Client::builder()
+        .key("anything")
+        .replay(&path)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(
+        replay.get_all_schools_im_thinking_about().await.unwrap(),
+        schools
+    );
+    assert_eq!(replay.get_scattergram_sources().await.unwrap(), sources);
+    match replay.get_college_info_by_uuid(&MICHIGAN).await {
+        Err(Error::NotRecorded { method, url }) => {
+            assert_eq!(method, "GET");
+            assert!(url.ends_with(&MICHIGAN.to_string()), "{url}");
+        }
+        other => panic!("{other:?}"),
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn cassettes_are_only_written_on_save() {
+    let path = temp_path("save.json");
+    let server = MockServer::start().await;
+    let client = server.client_builder().record(&path).build().await.unwrap();
+    client.get_scattergram_sources().await.unwrap();
+    assert!(!path.exists());
+    client.save_cassette().await.unwrap();
+    assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 2);
+
+    // Dropping the client only warns about the rest
+    client.get_application(202).await.unwrap();
+    drop(client);
+    assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 2);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn ds_api_tokens_are_redacted() {
+    let path = temp_path("ds-token.json");
+    let server = MockServer::start().await;
+    let config = include_str!("../fixtures/rewritten_config.js")
+        .replace("{{BASE}}", server.url().as_str())
+        .replace(
+            r#""DS_API_TEMP_TOKEN":"""#,
+            r#""DS_API_TEMP_TOKEN":"ds-temp-token""#,
+        );
+    server.set_fixture(Config::PATH, config);
+    let client = server.client_builder().record(&path).build().await.unwrap();
+    let token = client.config().unwrap().ds_api_temp_token.clone().unwrap();
+    assert_eq!(token.expose(), "ds-temp-token");
+    client.save_cassette().await.unwrap();
+
+    let recorded = std::fs::read_to_string(&path).unwrap();
+    assert!(!recorded.contains("ds-temp-token"), "{recorded}");
+    assert!(recorded.contains("[REDACTED]"), "{recorded}");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn errors_are_replayed_too() {
+    let path = temp_path("errors.json");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .key("not-the-key")
+        .record(&path)
+        .build()
+        .await
+        .unwrap();
+    let result = client.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::Unauthorized)), "{result:?}");
+    client.save_cassette().await.unwrap();
+
+    let replay = Client::builder()
+        .key("anything")
+        .replay(&path)
+        .build()
+        .await
+        .unwrap();
+    let result = replay.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::Unauthorized)), "{result:?}");
+    std::fs::remove_file(&path).unwrap();
+}
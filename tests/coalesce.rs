@@ -0,0 +1,68 @@
+//! Concurrent identical requests sharing one trip to the server
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{count, MICHIGAN, PURDUE};
+use naviance::{
+    testing::MockServer,
+    types::{College, Endpoint},
+    Error, RetryPolicy,
+};
+
+#[tokio::test]
+async fn identical_gets_are_coalesced() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let colleges = (0..10).map(|_| client.get_college_info_by_uuid(&MICHIGAN));
+    for college in futures::future::join_all(colleges).await {
+        college.unwrap();
+    }
+    assert_eq!(count(&server, College::PATH), 1);
+}
+
+#[tokio::test]
+async fn different_params_are_not_coalesced() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let (michigan, purdue) = futures::join!(
+        client.get_college_info_by_uuid(&MICHIGAN),
+        client.get_college_info_by_uuid(&PURDUE)
+    );
+    michigan.unwrap();
+    purdue.unwrap();
+    assert_eq!(count(&server, College::PATH), 2);
+}
+
+#[tokio::test]
+async fn errors_are_not_shared() {
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .retry(RetryPolicy::none())
+        .build()
+        .await
+        .unwrap();
+    server.fail_next(College::PATH, 500, 1);
+    let colleges = (0..3).map(|_| client.get_college_info_by_uuid(&MICHIGAN));
+    let colleges = futures::future::join_all(colleges).await;
+    // Whoever was waiting on the failed request fetches for themselves
+    let failed: Vec<_> = colleges.iter().filter_map(|c| c.as_ref().err()).collect();
+    assert!(matches!(failed[..], [Error::Server { .. }]), "{failed:?}");
+}
+
+#[tokio::test]
+async fn coalescing_can_be_turned_off() {
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .coalesce(false)
+        .build()
+        .await
+        .unwrap();
+    let colleges = (0..5).map(|_| client.get_college_info_by_uuid(&MICHIGAN));
+    for college in futures::future::join_all(colleges).await {
+        college.unwrap();
+    }
+    assert_eq!(count(&server, College::PATH), 5);
+}
@@ -0,0 +1,69 @@
+//! Fixtures and helpers shared by the integration tests. Not every test
+//! binary uses all of them
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use naviance::{
+    testing::{MockServer, RecordedRequest},
+    Client, ClientBuilder,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// The colleges on the thinking-about fixture, in order
+pub const MICHIGAN: Uuid = uuid::uuid!("9c2f7a4e-3b1d-4e8a-9f61-2d7c5b8a1e03");
+pub const NORTHEASTERN: Uuid = uuid::uuid!("4a8e1c2b-7d3f-4b6a-8e19-5c0d2f7a9b14");
+pub const PURDUE: Uuid = uuid::uuid!("e17b5d93-2c4a-4f08-b6d1-8a3e9c0f2d25");
+/// A college that isn't on any list yet
+pub const NEW_U: Uuid = uuid::uuid!("00000000-0000-0000-0000-000000000042");
+
+/// A client for `server` that has to find its own key
+pub fn keyless(server: &MockServer) -> ClientBuilder {
+    Client::builder()
+        .student_base(server.url())
+        .https_only(false)
+}
+
+/// How many requests `server` has seen under `path`
+pub fn count(server: &MockServer, path: &str) -> usize {
+    server
+        .requests()
+        .iter()
+        .filter(|r| r.path.starts_with(path))
+        .count()
+}
+
+/// Everything `server` has seen besides reads
+pub fn writes(server: &MockServer) -> Vec<RecordedRequest> {
+    server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method != "GET")
+        .collect()
+}
+
+/// An unsigned JWT carrying `claims`
+pub fn jwt(claims: &str) -> String {
+    format!(
+        "{}.{}.sig",
+        URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256"}"#),
+        URL_SAFE_NO_PAD.encode(claims)
+    )
+}
+
+/// A fixture parsed as JSON, with `edit` applied
+pub fn fixture(body: &str, edit: impl FnOnce(&mut Value)) -> String {
+    let mut body: Value = serde_json::from_str(body).unwrap();
+    edit(&mut body);
+    body.to_string()
+}
+
+/// A fresh path under the temp dir for `name`, unique to this test run
+pub fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("naviance-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let _ = std::fs::remove_file(&path);
+    path
+}
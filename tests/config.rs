@@ -0,0 +1,76 @@
+//! Finding the other Naviance services through `rewritten_config.js`
+#![cfg(feature = "testing")]
+
+use naviance::{
+    testing::MockServer,
+    types::{Config, Endpoint, ServiceKind},
+    Error,
+};
+
+/// Serves the config fixture with `field` set to `value`
+fn set_config(server: &MockServer, field: &str, value: &str) {
+    let config = include_str!("../fixtures/rewritten_config.js")
+        .replace("{{BASE}}", server.url().as_str())
+        .replace(
+            &format!(r#""{field}":"""#),
+            &format!(r#""{field}":"{value}""#),
+        );
+    server.set_fixture(Config::PATH, config);
+}
+
+#[tokio::test]
+async fn services_are_discovered() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let url = |path| Some(server.url().join(path).unwrap());
+    assert_eq!(client.service_url(ServiceKind::Api), url(""));
+    assert_eq!(client.service_url(ServiceKind::Login), url("login"));
+    assert_eq!(
+        client.service_url(ServiceKind::ScholarshipsUi),
+        url("scholarships")
+    );
+    // Blank in the config
+    assert_eq!(client.service_url(ServiceKind::DsApi), None);
+
+    let kinds: Vec<_> = client
+        .config()
+        .unwrap()
+        .services()
+        .map(|(k, _)| k)
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ServiceKind::Api,
+            ServiceKind::PortalApi,
+            ServiceKind::Login,
+            ServiceKind::CollegePlanningUi,
+            ServiceKind::ScholarshipsUi,
+            ServiceKind::SuperMatchUi,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn bare_hosts_become_https_urls() {
+    let server = MockServer::start().await;
+    set_config(&server, "HEADED2_API_DOMAIN", "api.headed2.example");
+    let client = server.client_builder().build().await.unwrap();
+    assert_eq!(
+        client
+            .service_url(ServiceKind::Headed2Api)
+            .map(String::from),
+        Some("https://api.headed2.example/".to_string())
+    );
+}
+
+#[tokio::test]
+async fn invalid_urls_only_fail_strictly() {
+    let server = MockServer::start().await;
+    set_config(&server, "DS_API_URL", "https://[not-a-host");
+    let client = server.client_builder().build().await.unwrap();
+    assert_eq!(client.service_url(ServiceKind::DsApi), None);
+
+    let result = server.client_builder().strict(true).build().await;
+    assert!(matches!(result, Err(Error::Decode(_))), "{result:?}");
+}
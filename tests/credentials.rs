@@ -0,0 +1,105 @@
+//! Where keys come from, and getting a new one when the old one stops working
+#![cfg(feature = "testing")]
+
+mod common;
+
+use chrono::Utc;
+use common::{jwt, keyless, temp_path};
+use naviance::{
+    credentials::{EnvKey, FileKey, StaticKey},
+    login::Login,
+    testing::{MockServer, MOCK_KEY, MOCK_PASSWORD, MOCK_USERNAME},
+    Error,
+};
+
+/// A key that expired a minute ago
+fn expired() -> String {
+    let exp = Utc::now().timestamp() - 60;
+    jwt(&format!(r#"{{"sub":"user-42","exp":{exp}}}"#))
+}
+
+#[tokio::test]
+async fn static_keys_stay_rejected() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.set_key("rotated-key");
+    let result = client.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::Unauthorized)), "{result:?}");
+}
+
+#[tokio::test]
+async fn expired_keys_are_replaced_by_the_provider() {
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .key(expired())
+        .credentials(StaticKey(MOCK_KEY.into()))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(client.key().expose(), MOCK_KEY);
+    client.get_scattergram_sources().await.unwrap();
+}
+
+#[tokio::test]
+async fn expired_keys_are_replaced_by_logging_in() {
+    let server = MockServer::start().await;
+    let client = keyless(&server)
+        .key(expired())
+        .login(Login::password(MOCK_USERNAME, MOCK_PASSWORD))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(client.key().expose(), MOCK_KEY);
+    client.get_scattergram_sources().await.unwrap();
+}
+
+#[tokio::test]
+async fn file_keys_are_reread_on_401() {
+    let path = temp_path("key-file");
+    std::fs::write(&path, format!("{MOCK_KEY}\n")).unwrap();
+    let server = MockServer::start().await;
+    let client = keyless(&server)
+        .credentials(FileKey(path.clone()))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(client.key().expose(), MOCK_KEY);
+
+    server.set_key("rotated-key");
+    let result = client.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::Unauthorized)), "{result:?}");
+    std::fs::write(&path, "rotated-key").unwrap();
+    client.get_scattergram_sources().await.unwrap();
+    // Every clone gets the new key
+    assert_eq!(client.clone().key().expose(), "rotated-key");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn missing_key_files_fail() {
+    let server = MockServer::start().await;
+    let result = keyless(&server)
+        .credentials(FileKey(temp_path("no-key-file")))
+        .build()
+        .await;
+    assert!(matches!(result, Err(Error::Io(_))), "{result:?}");
+}
+
+#[tokio::test]
+async fn env_keys() {
+    // Cargo sets this for every test binary, so nothing has to set_var it
+    let server = MockServer::start_with_key(env!("CARGO_PKG_NAME")).await;
+    let client = keyless(&server)
+        .credentials(EnvKey("CARGO_PKG_NAME".to_string()))
+        .build()
+        .await
+        .unwrap();
+    client.get_scattergram_sources().await.unwrap();
+
+    let result = keyless(&server)
+        .credentials(EnvKey("NAVIANCE_TEST_UNSET_KEY".to_string()))
+        .build()
+        .await;
+    assert!(matches!(result, Err(Error::Other(_))), "{result:?}");
+}
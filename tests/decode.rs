@@ -0,0 +1,55 @@
+//! What's left to go on when a response doesn't match our types
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{fixture, temp_path, MICHIGAN};
+use naviance::{
+    testing::MockServer,
+    types::{College, Endpoint},
+    Error,
+};
+use serde_json::json;
+
+const COLLEGE: &str = include_str!("../fixtures/college.json");
+
+#[tokio::test]
+async fn decode_errors_point_at_the_bad_value() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.set_fixture(
+        College::PATH,
+        fixture(COLLEGE, |body| body["latitude"] = json!("north")),
+    );
+    let e = match client.get_college_info_by_uuid(&MICHIGAN).await {
+        Err(Error::Decode(e)) => e,
+        other => panic!("{other:?}"),
+    };
+    assert_eq!(e.endpoint, College::PATH);
+    assert_eq!(e.json_path, "latitude");
+    assert_eq!(e.expected.as_deref(), Some("f64"));
+    assert_eq!(e.value_snippet.as_deref(), Some(r#""north""#));
+    assert!(e.dump.is_none());
+    assert!(e.to_string().contains("latitude"), "{e}");
+}
+
+#[tokio::test]
+async fn bodies_that_fail_to_decode_are_dumped() {
+    let dir = temp_path("dump");
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .debug_dump_dir(&dir)
+        .build()
+        .await
+        .unwrap();
+    let body = fixture(COLLEGE, |body| body["latitude"] = json!("north"));
+    server.set_fixture(College::PATH, body.clone());
+    let dump = match client.get_college_info_by_uuid(&MICHIGAN).await {
+        Err(Error::Decode(e)) => e.dump.unwrap(),
+        other => panic!("{other:?}"),
+    };
+    assert!(dump.starts_with(&dir));
+    assert_eq!(std::fs::read_to_string(&dump).unwrap(), body);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,47 @@
+//! Drift detection: fields the API sends that our types don't know about
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{fixture, MICHIGAN};
+use naviance::{
+    drift::{Drift, JsonType},
+    testing::MockServer,
+    types::{College, Endpoint},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn drift_is_reported() {
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .detect_drift(true)
+        .build()
+        .await
+        .unwrap();
+    server.set_fixture(
+        College::PATH,
+        fixture(include_str!("../fixtures/college.json"), |body| {
+            body["mascot"] = json!("Wolverine")
+        }),
+    );
+    // Lenient mode just skips it
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    let report = client.drift_report().unwrap();
+    assert_eq!(
+        report.endpoints[College::PATH].get("mascot"),
+        Some(&Drift::New {
+            found: JsonType::String,
+        })
+    );
+    assert!(report.to_string().contains("+ mascot (string)"), "{report}");
+}
+
+#[tokio::test]
+async fn no_drift_without_detection() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert!(client.drift_report().is_none());
+}
@@ -0,0 +1,86 @@
+//! Turning failed responses into the matching error variant
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::MICHIGAN;
+use http::StatusCode;
+use naviance::{
+    testing::MockServer,
+    types::{College, Endpoint, ScattergramSources},
+    Error, RetryPolicy,
+};
+
+#[tokio::test]
+async fn rejected_key_is_unauthorized() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.set_key("some-other-key");
+    let result = client.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::Unauthorized)), "{result:?}");
+}
+
+#[tokio::test]
+async fn unknown_ids_are_not_found() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    match client.get_application(999).await {
+        Err(Error::NotFound { endpoint, id }) => {
+            assert_eq!(endpoint, "/application");
+            assert_eq!(id.as_deref(), Some("999"));
+        }
+        other => panic!("{other:?}"),
+    }
+
+    server.remove_fixture(College::PATH);
+    match client.get_college_info_by_uuid(&MICHIGAN).await {
+        Err(Error::NotFound { id, .. }) => assert_eq!(id, Some(MICHIGAN.to_string())),
+        other => panic!("{other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn not_found_ids_ignore_the_api_base_path() {
+    let server = MockServer::start().await;
+    // Nothing is served under /api, so everything there is a 404
+    let client = server
+        .client_builder()
+        .api_base(server.url().join("api/").unwrap())
+        .build()
+        .await
+        .unwrap();
+    match client.get_application(202).await {
+        Err(Error::NotFound { endpoint, id }) => {
+            assert_eq!(endpoint, "/application");
+            assert_eq!(id.as_deref(), Some("202"));
+        }
+        other => panic!("{other:?}"),
+    }
+    match client.get_scattergram_sources().await {
+        Err(Error::NotFound { id, .. }) => assert_eq!(id, None),
+        other => panic!("{other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn server_and_client_errors_are_told_apart() {
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .retry(RetryPolicy::none())
+        .build()
+        .await
+        .unwrap();
+    server.fail_next(ScattergramSources::PATH, 502, 1);
+    match client.get_scattergram_sources().await {
+        Err(Error::Server { status, .. }) => assert_eq!(status, StatusCode::BAD_GATEWAY),
+        other => panic!("{other:?}"),
+    }
+    server.fail_next(ScattergramSources::PATH, 422, 1);
+    match client.get_scattergram_sources().await {
+        Err(Error::Status { status, .. }) => {
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY)
+        }
+        other => panic!("{other:?}"),
+    }
+}
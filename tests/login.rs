@@ -0,0 +1,107 @@
+//! Password and SSO logins against the mock login service, which lives
+//! under `/login/` like `UNIFIED_USER_LOGIN_URL` says
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::keyless;
+use naviance::{
+    login::Login,
+    testing::{MockServer, MOCK_KEY, MOCK_PASSWORD, MOCK_SSO_CODE, MOCK_USERNAME},
+    Error,
+};
+use url::Url;
+
+fn posts(server: &MockServer) -> Vec<String> {
+    server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "POST")
+        .map(|r| r.path)
+        .collect()
+}
+
+/// Follows the mock SSO provider's redirect back to `redirect_uri`
+async fn sso_redirect(server: &MockServer) -> Url {
+    let redirect_uri = Url::parse("http://localhost/callback").unwrap();
+    let start = keyless(server)
+        .sso_url("1234", &redirect_uri)
+        .await
+        .unwrap();
+    assert_eq!(start.path(), "/login/sso");
+    let http = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+    let response = http.get(start).send().await.unwrap();
+    let location = response.headers()["location"].to_str().unwrap();
+    Url::parse(location).unwrap()
+}
+
+#[tokio::test]
+async fn password_login() {
+    let server = MockServer::start().await;
+    let client = keyless(&server)
+        .login(Login::password(MOCK_USERNAME, MOCK_PASSWORD))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(client.key().expose(), MOCK_KEY);
+    client.get_scattergram_sources().await.unwrap();
+    assert_eq!(posts(&server), ["/login/authenticate"]);
+}
+
+#[tokio::test]
+async fn wrong_password() {
+    let server = MockServer::start().await;
+    let result = keyless(&server)
+        .login(Login::password(MOCK_USERNAME, "hunter2"))
+        .build()
+        .await;
+    assert!(matches!(result, Err(Error::LoginFailed(_))), "{result:?}");
+}
+
+#[tokio::test]
+async fn password_login_again_on_401() {
+    let server = MockServer::start().await;
+    let client = keyless(&server)
+        .login(Login::password(MOCK_USERNAME, MOCK_PASSWORD))
+        .build()
+        .await
+        .unwrap();
+    server.set_key("rotated-key");
+    client.get_scattergram_sources().await.unwrap();
+    assert_eq!(client.key().expose(), "rotated-key");
+    assert_eq!(posts(&server), ["/login/authenticate"; 2]);
+}
+
+#[tokio::test]
+async fn sso_login() {
+    let server = MockServer::start().await;
+    let redirect = sso_redirect(&server).await;
+    assert!(redirect
+        .query_pairs()
+        .any(|(name, value)| name == "code" && value == MOCK_SSO_CODE));
+    let client = keyless(&server)
+        .login(Login::SsoRedirect(redirect))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(client.key().expose(), MOCK_KEY);
+    assert_eq!(posts(&server), ["/login/sso/token"]);
+}
+
+#[tokio::test]
+async fn sso_code_is_not_replayed_on_401() {
+    let server = MockServer::start().await;
+    let redirect = sso_redirect(&server).await;
+    let client = keyless(&server)
+        .login(Login::SsoRedirect(redirect))
+        .build()
+        .await
+        .unwrap();
+    server.set_key("rotated-key");
+    let result = client.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::LoginFailed(_))), "{result:?}");
+    assert_eq!(posts(&server), ["/login/sso/token"]);
+}
@@ -0,0 +1,122 @@
+//! The mock server itself: what it serves, what it records and the failures
+//! it can be told to inject
+#![cfg(feature = "testing")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{count, MICHIGAN};
+use naviance::{
+    testing::{MockServer, RecordedRequest},
+    types::{Application, College, Endpoint, ScattergramSources, SchoolsImThinkingAbout},
+    Error, RetryPolicy,
+};
+
+#[tokio::test]
+async fn requests_are_recorded() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    client
+        .fetch_page::<SchoolsImThinkingAbout>(&(), 1, Some(2))
+        .await
+        .unwrap();
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/rewritten_config.js");
+    assert_eq!(
+        requests[1],
+        RecordedRequest {
+            method:     "GET".to_string(),
+            path:       SchoolsImThinkingAbout::PATH.to_string(),
+            query:      Some("page=1&limit=2".to_string()),
+            authorized: true,
+            body:       String::new(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn wrong_keys_are_rejected() {
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .key("not-the-key")
+        .build()
+        .await
+        .unwrap();
+    let result = client.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::Unauthorized)), "{result:?}");
+    assert!(!server.requests().last().unwrap().authorized);
+
+    // Until the server comes round to it
+    server.set_key("not-the-key");
+    client.get_scattergram_sources().await.unwrap();
+}
+
+#[tokio::test]
+async fn fixtures_can_be_swapped_out() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.set_fixture(ScattergramSources::PATH, "[]");
+    assert!(client.get_scattergram_sources().await.unwrap().is_empty());
+    server.remove_fixture(ScattergramSources::PATH);
+    let result = client.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::NotFound { .. })), "{result:?}");
+}
+
+#[tokio::test]
+async fn ids_fall_back_to_the_parent_fixture() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    // Any UUID gets the one college
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    assert_eq!(count(&server, College::PATH), 1);
+    // ...but there's only the one application
+    client.get_application(202).await.unwrap();
+    let result = client.get_application(203).await;
+    assert!(matches!(result, Err(Error::NotFound { .. })), "{result:?}");
+    assert_eq!(count(&server, Application::PATH), 2);
+}
+
+#[tokio::test]
+async fn paged_fixtures_are_sliced() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let page = client
+        .fetch_page::<SchoolsImThinkingAbout>(&(), 2, Some(2))
+        .await
+        .unwrap();
+    assert_eq!(page.page, Some(2));
+    assert_eq!(page.total_items, Some(3));
+    assert_eq!(page.total_pages, Some(2));
+    assert_eq!(page.data.len(), 1);
+    assert_eq!(page.data[0].id, Some(103));
+}
+
+#[tokio::test]
+async fn injected_failures_run_out() {
+    let server = MockServer::start().await;
+    let client = server
+        .client_builder()
+        .retry(RetryPolicy::none())
+        .build()
+        .await
+        .unwrap();
+    server.fail_next(ScattergramSources::PATH, 503, 2);
+    // Other paths are left alone
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    for _ in 0..2 {
+        let result = client.get_scattergram_sources().await;
+        assert!(matches!(result, Err(Error::Server { .. })), "{result:?}");
+    }
+    client.get_scattergram_sources().await.unwrap();
+
+    server.rate_limit_next(ScattergramSources::PATH, 1, Duration::from_secs(7));
+    match client.get_scattergram_sources().await {
+        Err(Error::RateLimited { retry_after }) => {
+            assert_eq!(retry_after, Some(Duration::from_secs(7)))
+        }
+        other => panic!("{other:?}"),
+    }
+    client.get_scattergram_sources().await.unwrap();
+}
@@ -0,0 +1,70 @@
+//! Walking paged endpoints page by page against the mock server, which
+//! slices its fixtures by `page` and `limit`
+#![cfg(feature = "testing")]
+
+use futures::StreamExt;
+use naviance::{
+    testing::MockServer,
+    types::{CollegeQuery, CollegeSearch, Endpoint, SchoolsImThinkingAbout},
+};
+
+/// The `page`s asked for under `path`, in order
+fn pages(server: &MockServer, path: &str) -> Vec<String> {
+    server
+        .requests()
+        .into_iter()
+        .filter(|r| r.path == path)
+        .filter_map(|r| {
+            let query = r.query?;
+            let (_, page) =
+                url::form_urlencoded::parse(query.as_bytes()).find(|(k, _)| k == "page")?;
+            Some(page.into_owned())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn every_page_in_order() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let schools = client
+        .paginate::<SchoolsImThinkingAbout>()
+        .page_size(2)
+        .collect_all()
+        .await
+        .unwrap();
+    let ids: Vec<_> = schools.iter().map(|s| s.id.unwrap()).collect();
+    assert_eq!(ids, [101, 102, 103]);
+    assert_eq!(pages(&server, SchoolsImThinkingAbout::PATH), ["1", "2"]);
+}
+
+#[tokio::test]
+async fn concurrent_pages_stay_in_order() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let colleges = client
+        .search_colleges_paginated(CollegeQuery::new())
+        .page_size(1)
+        .concurrency(4)
+        .collect_all()
+        .await
+        .unwrap();
+    let expected = client.search_colleges(&CollegeQuery::new()).await.unwrap();
+    assert_eq!(colleges, expected.data);
+    assert_eq!(pages(&server, CollegeSearch::PATH).len(), 6);
+}
+
+#[tokio::test]
+async fn streams_stop_at_the_first_error() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let mut stream = client
+        .paginate::<SchoolsImThinkingAbout>()
+        .page_size(2)
+        .stream();
+    assert!(stream.next().await.unwrap().is_ok());
+    assert!(stream.next().await.unwrap().is_ok());
+    server.fail_next(SchoolsImThinkingAbout::PATH, 404, 1);
+    assert!(stream.next().await.unwrap().is_err());
+    assert!(stream.next().await.is_none());
+}
@@ -0,0 +1,78 @@
+//! Path segments, query strings and bodies from an endpoint's `Params`
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::MICHIGAN;
+use naviance::{
+    testing::MockServer,
+    types::{Application, AuthEndpoint, College, CollegeQuery, CollegeSearch, Endpoint},
+    Result,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// A UUID-keyed endpoint nobody has written a client method for
+struct RawCollege;
+
+impl Endpoint for RawCollege {
+    type Params = Uuid;
+    type Response = Value;
+
+    const METHOD: http::Method = http::Method::GET;
+    const PATH: &'static str = College::PATH;
+
+    fn extract(body: &[u8]) -> Result<Self::Response> { Ok(serde_json::from_slice(body)?) }
+}
+
+impl AuthEndpoint for RawCollege {}
+
+#[tokio::test]
+async fn ids_become_path_segments() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    client.fetch_auth_with::<College>(&MICHIGAN).await.unwrap();
+    client.fetch_auth_with::<Application>(&202).await.unwrap();
+    let requests = server.requests();
+    let paths: Vec<_> = requests[1..].iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            format!("{}/{MICHIGAN}", College::PATH),
+            format!("{}/202", Application::PATH)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn new_endpoints_only_need_a_type() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let college = client
+        .fetch_auth_with::<RawCollege>(&MICHIGAN)
+        .await
+        .unwrap();
+    assert!(college["name"].is_string(), "{college}");
+    let last = server.requests().pop().unwrap();
+    assert_eq!(last.path, format!("{}/{MICHIGAN}", College::PATH));
+}
+
+#[tokio::test]
+async fn queries_become_query_strings() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let query = CollegeQuery::new().state("MI");
+    client
+        .fetch_auth_with::<CollegeSearch>(&query)
+        .await
+        .unwrap();
+    let last = server.requests().pop().unwrap();
+    assert_eq!(last.path, CollegeSearch::PATH);
+    let pairs: Vec<_> = url::form_urlencoded::parse(last.query.unwrap().as_bytes())
+        .into_owned()
+        .collect();
+    assert!(
+        pairs.contains(&("state".to_string(), "MI".to_string())),
+        "{pairs:?}"
+    );
+}
@@ -0,0 +1,55 @@
+//! The client-side rate limit, shared by every clone of a client
+#![cfg(feature = "testing")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use naviance::{testing::MockServer, Client, RateLimit};
+
+async fn limited(server: &MockServer, burst: u32) -> Client {
+    server
+        .client_builder()
+        .api_base(server.url())
+        // Identical requests would share one trip otherwise
+        .coalesce(false)
+        .rate_limit(RateLimit {
+            requests_per_second: 20.,
+            burst,
+            max_in_flight: 8,
+        })
+        .build()
+        .await
+        .unwrap()
+}
+
+/// Fires off five requests at once from separate clones
+async fn five_at_once(client: &Client) -> Duration {
+    let start = Instant::now();
+    let requests = (0..5).map(|_| {
+        let client = client.clone();
+        async move { client.get_scattergram_sources().await }
+    });
+    for result in futures::future::join_all(requests).await {
+        result.unwrap();
+    }
+    start.elapsed()
+}
+
+#[tokio::test]
+async fn clones_share_the_limit() {
+    let server = MockServer::start().await;
+    let client = limited(&server, 1).await;
+    // The first goes straight out, the other four 50ms apart
+    let elapsed = five_at_once(&client).await;
+    assert!(elapsed >= Duration::from_millis(190), "{elapsed:?}");
+    assert_eq!(common::count(&server, "/college"), 5);
+}
+
+#[tokio::test]
+async fn bursts_go_straight_out() {
+    let server = MockServer::start().await;
+    let client = limited(&server, 5).await;
+    let elapsed = five_at_once(&client).await;
+    assert!(elapsed < Duration::from_millis(150), "{elapsed:?}");
+}
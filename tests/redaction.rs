@@ -0,0 +1,43 @@
+//! Keeping keys and other secrets out of `Debug` output and logs
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::jwt;
+use naviance::{
+    testing::MockServer,
+    types::{Config, Endpoint},
+    SecretKey,
+};
+
+#[test]
+fn secret_keys_are_redacted() {
+    let key = SecretKey::new("hunter2");
+    assert_eq!(format!("{key:?}"), "SecretKey([REDACTED])");
+    assert_eq!(key.to_string(), "[REDACTED]");
+    assert_eq!(key.expose(), "hunter2");
+}
+
+#[tokio::test]
+async fn secrets_stay_out_of_debug_output() {
+    let key = jwt(r#"{"sub":"555001","exp":4102444800,"email":"kid@example.com"}"#);
+    let server = MockServer::start_with_key(key.clone()).await;
+    let config = include_str!("../fixtures/rewritten_config.js")
+        .replace("{{BASE}}", server.url().as_str())
+        .replace(
+            r#""DS_API_TEMP_TOKEN":"""#,
+            r#""DS_API_TEMP_TOKEN":"ds-secret""#,
+        );
+    server.set_fixture(Config::PATH, config);
+    let client = server.client_builder().build().await.unwrap();
+    let ds_token = client.config().unwrap().ds_api_temp_token.as_ref();
+    assert_eq!(ds_token.unwrap().expose(), "ds-secret");
+
+    let debug = format!("{client:?} {:?}", client.token_claims());
+    for secret in [key.as_str(), "ds-secret", "kid@example.com"] {
+        assert!(!debug.contains(secret), "{secret} in {debug}");
+    }
+    // The names are fine
+    assert!(debug.contains("email"), "{debug}");
+    assert!(debug.contains("555001"), "{debug}");
+}
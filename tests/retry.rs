@@ -0,0 +1,114 @@
+//! Retrying failed requests: which ones, how often, and when to give up
+#![cfg(feature = "testing")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{count, MICHIGAN};
+use http::StatusCode;
+use naviance::{
+    testing::MockServer,
+    types::{Endpoint, NewSchool, ScattergramSources, SchoolsImThinkingAbout},
+    Error, RetryPolicy,
+};
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.fail_next(ScattergramSources::PATH, 503, 2);
+    client.get_scattergram_sources().await.unwrap();
+    assert_eq!(count(&server, ScattergramSources::PATH), 3);
+}
+
+#[tokio::test]
+async fn server_errors_give_up_after_max_attempts() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.fail_next(ScattergramSources::PATH, 500, 10);
+    match client.get_scattergram_sources().await {
+        Err(Error::Server { status, .. }) => assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR),
+        other => panic!("{other:?}"),
+    }
+    let max_attempts = RetryPolicy::default().max_attempts as usize;
+    assert_eq!(count(&server, ScattergramSources::PATH), max_attempts);
+}
+
+#[tokio::test]
+async fn only_idempotent_writes_are_retried() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.fail_next(SchoolsImThinkingAbout::PATH, 503, 1);
+    let result = client
+        .add_school_im_thinking_about(&NewSchool::new(MICHIGAN))
+        .await;
+    assert!(matches!(result, Err(Error::Server { .. })), "{result:?}");
+    assert_eq!(count(&server, SchoolsImThinkingAbout::PATH), 1);
+
+    // Deleting twice is the same as deleting once
+    server.fail_next(SchoolsImThinkingAbout::PATH, 503, 1);
+    client.remove_school_im_thinking_about(101).await.unwrap();
+    assert_eq!(count(&server, SchoolsImThinkingAbout::PATH), 3);
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.fail_next(ScattergramSources::PATH, 400, 1);
+    let result = client.get_scattergram_sources().await;
+    assert!(matches!(result, Err(Error::Status { .. })), "{result:?}");
+    assert_eq!(count(&server, ScattergramSources::PATH), 1);
+}
+
+#[tokio::test]
+async fn rate_limited_requests_wait_for_retry_after() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.rate_limit_next(ScattergramSources::PATH, 1, Duration::ZERO);
+    client.get_scattergram_sources().await.unwrap();
+    assert_eq!(count(&server, ScattergramSources::PATH), 2);
+}
+
+#[tokio::test]
+async fn retry_after_past_max_backoff_gives_up() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.rate_limit_next(ScattergramSources::PATH, 1, Duration::from_secs(60));
+    match client.get_scattergram_sources().await {
+        Err(Error::RateLimited { retry_after }) => {
+            assert_eq!(retry_after, Some(Duration::from_secs(60)))
+        }
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(count(&server, ScattergramSources::PATH), 1);
+}
+
+#[tokio::test]
+async fn unavailable_past_max_backoff_is_a_server_error() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.unavailable_next(ScattergramSources::PATH, 1, Duration::from_secs(60));
+    match client.get_scattergram_sources().await {
+        Err(Error::Server {
+            status,
+            retry_after,
+            ..
+        }) => {
+            assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(retry_after, Some(Duration::from_secs(60)));
+        }
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(count(&server, ScattergramSources::PATH), 1);
+}
+
+#[tokio::test]
+async fn unavailable_retries_wait_for_retry_after() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    server.unavailable_next(ScattergramSources::PATH, 1, Duration::ZERO);
+    client.get_scattergram_sources().await.unwrap();
+    assert_eq!(count(&server, ScattergramSources::PATH), 2);
+}
@@ -0,0 +1,36 @@
+//! Runs every file in `fixtures/rewritten_config` through the config parser.
+//! `bad_*.js` should be rejected with a useful error, everything else should
+//! come out with `API_HOST` intact
+
+use std::path::Path;
+
+use naviance::{
+    types::{Config, Endpoint},
+    Error,
+};
+
+#[test]
+fn rewritten_config_corpus() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/rewritten_config");
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    assert!(!files.is_empty());
+    for path in files {
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let body = std::fs::read(&path).unwrap();
+        match (name.starts_with("bad_"), Config::extract(&body)) {
+            (false, Ok(config)) => assert_eq!(
+                config.api_host.as_ref().map(|url| url.as_str()),
+                Some("https://api.example.com/"),
+                "{name}"
+            ),
+            (true, Err(Error::ConfigParse(message))) => {
+                assert!(message.contains("config.js"), "{name}: {message}")
+            }
+            (_, result) => panic!("{name}: unexpected {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! College search filters, checked against the mock server's search fixture
+//! (three colleges in Michigan, two in Ohio and one in Ontario)
+#![cfg(feature = "testing")]
+
+use naviance::{
+    testing::MockServer,
+    types::{CollegeQuery, CollegeSearch, CollegeSize, Endpoint, Sector},
+    Client,
+};
+
+async fn names(client: &Client, query: CollegeQuery) -> Vec<String> {
+    client
+        .search_colleges_paginated(query)
+        .collect_all()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.name.unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn filters() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    assert_eq!(names(&client, CollegeQuery::new()).await.len(), 6);
+    assert_eq!(
+        names(&client, CollegeQuery::new().state("OH")).await,
+        ["Ohio State University", "Oberlin College"]
+    );
+    assert_eq!(
+        names(&client, CollegeQuery::new().state("MI").state("OH"))
+            .await
+            .len(),
+        5
+    );
+    assert_eq!(
+        names(&client, CollegeQuery::new().country("CA")).await,
+        ["University of Toronto"]
+    );
+    assert_eq!(
+        names(&client, CollegeQuery::new().name("michigan")).await,
+        ["University of Michigan", "Michigan State University"]
+    );
+    assert_eq!(
+        names(
+            &client,
+            CollegeQuery::new()
+                .state("MI")
+                .sector(Sector::PrivateNonprofit4Year)
+        )
+        .await,
+        ["Kalamazoo College"]
+    );
+    assert_eq!(
+        names(&client, CollegeQuery::new().size(CollegeSize::Small)).await,
+        ["Kalamazoo College", "Oberlin College"]
+    );
+    assert!(names(&client, CollegeQuery::new().state("ZZ"))
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn query_string() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let query = CollegeQuery::new()
+        .name("state university")
+        .state("MI")
+        .sector(Sector::Public4Year)
+        .size(CollegeSize::VeryLarge);
+    let page = client.search_colleges(&query).await.unwrap();
+    assert_eq!(page.total_items, Some(1));
+    assert_eq!(page.data[0].sector, Some(Sector::Public4Year.code()));
+    assert_eq!(page.data[0].size, Some(CollegeSize::VeryLarge.code()));
+    let request = server.requests().pop().unwrap();
+    assert_eq!(request.path, CollegeSearch::PATH);
+    assert_eq!(
+        request.query.unwrap(),
+        "name=state+university&state=MI&sector=1&size=5"
+    );
+}
@@ -0,0 +1,63 @@
+//! Everyone fans requests out with `tokio::spawn`, so the generic fetches
+//! have to stay `Send + 'static`. The helpers are generic on purpose - this
+//! stops compiling if any endpoint's future loses that
+#![cfg(feature = "testing")]
+
+use naviance::{
+    testing::MockServer,
+    types::{
+        AuthEndpoint, College, CollegeQuery, CollegeSearch, Config, PagedEndpoint, PublicEndpoint,
+        SchoolsImThinkingAbout,
+    },
+    Client, Result,
+};
+use tokio::task::JoinHandle;
+
+fn spawn_fetch<T: PublicEndpoint>(
+    client: &Client,
+    params: T::Params,
+) -> JoinHandle<Result<T::Response>> {
+    let client = client.clone();
+    tokio::spawn(async move { client.fetch_with::<T>(&params).await })
+}
+
+fn spawn_fetch_auth<T: AuthEndpoint>(
+    client: &Client,
+    params: T::Params,
+) -> JoinHandle<Result<T::Response>> {
+    let client = client.clone();
+    tokio::spawn(async move { client.fetch_auth_with::<T>(&params).await })
+}
+
+fn spawn_fetch_all<T: PagedEndpoint>(
+    client: &Client,
+    params: T::Params,
+) -> JoinHandle<Result<Vec<T::Item>>> {
+    tokio::spawn(client.paginate_with::<T>(params).collect_all())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fetches_can_be_spawned() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+
+    let config = spawn_fetch::<Config>(&client, ());
+    let schools = spawn_fetch_all::<SchoolsImThinkingAbout>(&client, ());
+    let search = spawn_fetch_all::<CollegeSearch>(&client, CollegeQuery::new().state("MI"));
+    let first_page = spawn_fetch_auth::<SchoolsImThinkingAbout>(&client, ());
+
+    assert!(config.await.unwrap().unwrap().api_host.is_some());
+    let schools = schools.await.unwrap().unwrap();
+    assert_eq!(schools.len(), 3);
+    assert!(!search.await.unwrap().unwrap().is_empty());
+    assert!(first_page.await.unwrap().is_ok());
+
+    let colleges: Vec<_> = schools
+        .iter()
+        .filter_map(|school| school.college.as_ref()?.uuid)
+        .map(|uuid| spawn_fetch_auth::<College>(&client, uuid))
+        .collect();
+    for college in colleges {
+        college.await.unwrap().unwrap();
+    }
+}
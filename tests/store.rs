@@ -0,0 +1,66 @@
+//! The encrypted credential store and the profiles in it
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{keyless, temp_path};
+use naviance::{
+    store::{CredentialStore, Profile, StoredLogin, StudentInfo},
+    testing::{MockServer, MOCK_KEY, MOCK_PASSWORD, MOCK_USERNAME},
+    Error,
+};
+
+#[tokio::test]
+async fn profiles_round_trip_encrypted() {
+    let path = temp_path("store.enc");
+    let mut store = CredentialStore::open(&path, "passphrase").unwrap();
+    store.insert(
+        "token",
+        Profile {
+            token: Some(MOCK_KEY.into()),
+            ..Profile::default()
+        },
+    );
+    store.insert(
+        "login",
+        Profile {
+            login: Some(StoredLogin {
+                username: MOCK_USERNAME.to_string(),
+                password: MOCK_PASSWORD.into(),
+                school:   None,
+            }),
+            student: StudentInfo {
+                name: Some("Alex".to_string()),
+                ..StudentInfo::default()
+            },
+            ..Profile::default()
+        },
+    );
+    store.set_active("login").unwrap();
+    store.save().unwrap();
+
+    let on_disk = std::fs::read_to_string(&path).unwrap();
+    for secret in [MOCK_KEY, MOCK_PASSWORD, "Alex"] {
+        assert!(!on_disk.contains(secret), "{secret}");
+    }
+    let result = CredentialStore::open(&path, "wrong");
+    assert!(matches!(result, Err(Error::Store(_))), "{result:?}");
+
+    let store = CredentialStore::open(&path, "passphrase").unwrap();
+    let (name, profile) = store.active().unwrap();
+    assert_eq!(name, "login");
+    assert_eq!(profile.student.name.as_deref(), Some("Alex"));
+    assert!(store.get("nobody").is_none());
+    let result = CredentialStore::open(&path, "passphrase")
+        .unwrap()
+        .set_active("nobody");
+    assert!(result.is_err());
+
+    let server = MockServer::start().await;
+    for name in ["token", "login"] {
+        let profile = store.get(name).unwrap();
+        let client = profile.apply(keyless(&server)).build().await.unwrap();
+        client.get_scattergram_sources().await.unwrap();
+    }
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,130 @@
+//! Strict mode against the mock server's fixtures, which are real responses
+//! with the personal bits swapped out
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{fixture, MICHIGAN};
+use naviance::{
+    testing::MockServer,
+    types::{Application, ApplicationStatistics, College, CollegeQuery, Endpoint, TypeName},
+    Client, Error,
+};
+use serde_json::json;
+
+const APPLICATION: &str = include_str!("../fixtures/application.json");
+
+/// A strict and a lenient client for `server`
+async fn clients(server: &MockServer) -> (Client, Client) {
+    let strict = server.client_builder().strict(true).build().await.unwrap();
+    let lenient = server.client_builder().build().await.unwrap();
+    (strict, lenient)
+}
+
+fn json_path(result: Result<impl std::fmt::Debug, Error>) -> String {
+    match result {
+        Err(Error::Decode(e)) => e.json_path,
+        other => panic!("{other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn every_fixture_decodes_strictly() {
+    let server = MockServer::start().await;
+    let (client, _) = clients(&server).await;
+    client.get_all_schools_im_thinking_about().await.unwrap();
+    client.get_all_colleges_im_applying_to().await.unwrap();
+    client.get_application(202).await.unwrap();
+    client.get_scattergram_sources().await.unwrap();
+    client.get_college_info_by_uuid(&MICHIGAN).await.unwrap();
+    client
+        .search_colleges_paginated(CollegeQuery::new())
+        .collect_all()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn unknown_enum_values_only_fail_strictly() {
+    let server = MockServer::start().await;
+    let (strict, lenient) = clients(&server).await;
+    server.set_fixture(
+        Application::PATH,
+        fixture(APPLICATION, |body| {
+            body["applicationType"] = json!("SUPER_EARLY")
+        }),
+    );
+    assert_eq!(
+        json_path(strict.get_application(202).await),
+        "applicationType"
+    );
+    let application = lenient.get_application(202).await.unwrap();
+    assert_eq!(application.application_type, Some(TypeName::Unknown));
+}
+
+#[tokio::test]
+async fn unknown_fields_only_fail_strictly() {
+    let server = MockServer::start().await;
+    let (strict, lenient) = clients(&server).await;
+    server.set_fixture(
+        Application::PATH,
+        fixture(APPLICATION, |body| {
+            body["portalUrl"] = json!("https://example.edu/portal")
+        }),
+    );
+    assert_eq!(json_path(strict.get_application(202).await), "portalUrl");
+    let application = lenient.get_application(202).await.unwrap();
+    assert_eq!(application.id, Some(202));
+}
+
+#[tokio::test]
+async fn out_of_range_booleans_always_fail() {
+    let server = MockServer::start().await;
+    let (strict, lenient) = clients(&server).await;
+    server.set_fixture(
+        College::PATH,
+        fixture(include_str!("../fixtures/college.json"), |body| {
+            body["ssrRequired"] = json!(2)
+        }),
+    );
+    for client in [strict, lenient] {
+        let result = client.get_college_info_by_uuid(&MICHIGAN).await;
+        assert_eq!(json_path(result), "ssrRequired");
+    }
+}
+
+#[tokio::test]
+async fn application_statistics_fixture_decodes_strictly() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().strict(true).build().await.unwrap();
+    let stats = client
+        .get_application_stats_by_uuid(&MICHIGAN)
+        .await
+        .unwrap();
+    assert!(stats.scattergrams.is_some());
+    assert!(stats.user_info.is_some());
+
+    // Usually null, but they're kept when they aren't
+    server.set_fixture(
+        ApplicationStatistics::PATH,
+        fixture(
+            include_str!("../fixtures/application_statistics.json"),
+            |body| {
+                body["applicationStatistics"] = json!({ "applied": 12, "accepted": 7 });
+                body["applicationsByYear"] = json!([{ "year": 2023, "applied": 5 }]);
+            },
+        ),
+    );
+    let stats = client
+        .get_application_stats_by_uuid(&MICHIGAN)
+        .await
+        .unwrap();
+    assert_eq!(
+        stats.application_statistics,
+        Some(json!({ "applied": 12, "accepted": 7 }))
+    );
+    assert_eq!(
+        stats.applications_by_year,
+        Some(json!([{ "year": 2023, "applied": 5 }]))
+    );
+}
@@ -0,0 +1,82 @@
+//! Syncing a local target list onto the thinking-about list
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{writes, MICHIGAN, NORTHEASTERN};
+use naviance::{
+    sync::{Change, SyncPlan, TargetList},
+    testing::MockServer,
+    Error, Write,
+};
+
+const TARGETS: &str = r#"
+[[college]]
+uuid = "9c2f7a4e-3b1d-4e8a-9f61-2d7c5b8a1e03"
+name = "Michigan"
+interest_level = 3
+
+[[college]]
+uuid = "4a8e1c2b-7d3f-4b6a-8e19-5c0d2f7a9b14"
+interest_level = 4
+expected_outcome = 1
+
+[[college]]
+uuid = "00000000-0000-0000-0000-000000000042"
+name = "New U"
+interest_level = 2
+"#;
+
+const TARGETS_CSV: &str = "\
+uuid, name, interest_level, expected_outcome
+9c2f7a4e-3b1d-4e8a-9f61-2d7c5b8a1e03,Michigan,3,
+4a8e1c2b-7d3f-4b6a-8e19-5c0d2f7a9b14,,4,1
+00000000-0000-0000-0000-000000000042,New U,2,
+";
+
+#[test]
+fn target_lists() {
+    let targets = TargetList::from_toml(TARGETS).unwrap();
+    assert_eq!(targets.colleges.len(), 3);
+    assert_eq!(targets.colleges[1].uuid, NORTHEASTERN);
+    assert_eq!(targets.colleges[1].expected_outcome, Some(1));
+
+    assert_eq!(
+        TargetList::from_csv(TARGETS_CSV.as_bytes()).unwrap(),
+        targets
+    );
+
+    let twice = format!("{TARGETS}\n[[college]]\nuuid = \"{MICHIGAN}\"\n");
+    let result = TargetList::from_toml(&twice);
+    assert!(matches!(result, Err(Error::TargetList(_))), "{result:?}");
+    let result = TargetList::from_csv("uuid\nnot-a-uuid\n".as_bytes());
+    assert!(matches!(result, Err(Error::TargetList(_))), "{result:?}");
+}
+
+#[tokio::test]
+async fn sync_plan() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let targets = TargetList::from_toml(TARGETS).unwrap();
+    let plan = SyncPlan::fetch(&client, &targets).await.unwrap();
+    // Michigan is already right
+    assert!(matches!(
+        &plan.changes[..],
+        [
+            Change::Update { .. },
+            Change::Add { .. },
+            Change::Remove { id: 103, .. }
+        ]
+    ));
+
+    // A dry run changes nothing, so the plan stays the same
+    let dry = server.client_builder().dry_run(true).build().await.unwrap();
+    let planned = plan.apply(&dry).await.unwrap();
+    assert!(planned.iter().all(Write::is_dry_run));
+    assert!(writes(&server).is_empty());
+    assert_eq!(SyncPlan::fetch(&dry, &targets).await.unwrap(), plan);
+
+    assert_eq!(plan.apply(&client).await.unwrap().len(), 3);
+    assert_eq!(writes(&server).len(), 3);
+    assert!(SyncPlan::fetch(&client, &targets).await.unwrap().is_empty());
+}
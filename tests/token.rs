@@ -0,0 +1,103 @@
+//! What a JWT key says about itself: who it's for and when it runs out
+#![cfg(feature = "testing")]
+
+mod common;
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use common::jwt;
+use naviance::{testing::MockServer, Error};
+
+fn expiring_at(exp: i64) -> String {
+    jwt(&format!(
+        r#"{{"sub":"user-42","exp":{exp},"iat":1700000000}}"#
+    ))
+}
+
+/// Collects everything logged on this thread while it's alive
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+impl Logs {
+    fn capture(&self) -> tracing::subscriber::DefaultGuard {
+        let logs = self.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || logs.clone())
+            .finish();
+        tracing::subscriber::set_default(subscriber)
+    }
+
+    fn count(&self, needle: &str) -> usize {
+        String::from_utf8_lossy(&self.0.lock().unwrap())
+            .matches(needle)
+            .count()
+    }
+}
+
+#[tokio::test]
+async fn jwt_claims() {
+    let exp = Utc::now().timestamp() + 3600;
+    let server = MockServer::start_with_key(expiring_at(exp)).await;
+    let client = server.client_builder().build().await.unwrap();
+    assert_eq!(client.key_subject().as_deref(), Some("user-42"));
+    assert_eq!(client.key_expires_at(), DateTime::from_timestamp(exp, 0));
+    let claims = client.token_claims().unwrap();
+    assert_eq!(claims.issued_at, DateTime::from_timestamp(1700000000, 0));
+    client.get_scattergram_sources().await.unwrap();
+
+    // Not every key is a JWT
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    assert!(client.token_claims().is_none());
+    assert!(client.key_expires_at().is_none());
+}
+
+#[tokio::test]
+async fn expired_keys_fail_before_asking() {
+    let exp = Utc::now().timestamp() - 60;
+    let server = MockServer::start_with_key(expiring_at(exp)).await;
+    match server.client_builder().build().await {
+        Err(Error::KeyExpired { expired_at }) => {
+            assert_eq!(DateTime::from_timestamp(exp, 0), Some(expired_at))
+        }
+        other => panic!("{other:?}"),
+    }
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn keys_about_to_expire_warn_once() {
+    let logs = Logs::default();
+    let _guard = logs.capture();
+    let exp = Utc::now().timestamp() + 5 * 60;
+    let server = MockServer::start_with_key(expiring_at(exp)).await;
+    let client = server.client_builder().build().await.unwrap();
+    client.get_scattergram_sources().await.unwrap();
+    client.get_scattergram_sources().await.unwrap();
+    assert_eq!(logs.count("The API key expires at"), 1);
+}
+
+#[tokio::test]
+async fn keys_with_time_left_dont_warn() {
+    let logs = Logs::default();
+    let _guard = logs.capture();
+    let exp = Utc::now().timestamp() + 3600;
+    let server = MockServer::start_with_key(expiring_at(exp)).await;
+    let client = server.client_builder().build().await.unwrap();
+    client.get_scattergram_sources().await.unwrap();
+    assert_eq!(logs.count("The API key expires at"), 0);
+}
@@ -0,0 +1,154 @@
+//! Changing the thinking-about list, with and without dry-run mode
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{writes, NEW_U};
+use naviance::{
+    testing::MockServer,
+    types::{NewSchool, SchoolUpdate},
+    Error, Write,
+};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn add_update_remove() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let added = client
+        .add_school_im_thinking_about(&NewSchool {
+            interest_level: Some(2),
+            ..NewSchool::new(NEW_U)
+        })
+        .await
+        .unwrap()
+        .done()
+        .unwrap();
+    assert_eq!(added.id, Some(104));
+    assert_eq!(added.interest_level, Some(2));
+    assert_eq!(added.college.unwrap().uuid, Some(NEW_U));
+
+    let updated = client
+        .update_school_im_thinking_about(&SchoolUpdate {
+            id:               104,
+            interest_level:   None,
+            expected_outcome: Some(4),
+        })
+        .await
+        .unwrap()
+        .done()
+        .unwrap();
+    assert_eq!(updated.interest_level, Some(2));
+    assert_eq!(updated.expected_outcome, Some(4));
+    assert_eq!(
+        client
+            .get_all_schools_im_thinking_about()
+            .await
+            .unwrap()
+            .len(),
+        4
+    );
+
+    client.remove_school_im_thinking_about(104).await.unwrap();
+    assert_eq!(
+        client
+            .get_all_schools_im_thinking_about()
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+
+    let sent: Vec<_> = writes(&server)
+        .into_iter()
+        .map(|r| {
+            (
+                r.method,
+                r.path,
+                serde_json::from_str(&r.body).unwrap_or(Value::Null),
+            )
+        })
+        .collect();
+    let path = "/college/colleges-im-thinking-about";
+    assert_eq!(
+        sent,
+        [
+            (
+                "POST".to_string(),
+                path.to_string(),
+                json!({ "collegeUuid": NEW_U, "interestLevel": 2 })
+            ),
+            (
+                "PATCH".to_string(),
+                format!("{path}/104"),
+                json!({ "expectedOutcome": 4 })
+            ),
+            ("DELETE".to_string(), format!("{path}/104"), Value::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn unknown_school_ids_are_not_found() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().build().await.unwrap();
+    let update = SchoolUpdate {
+        id:               9999,
+        interest_level:   Some(1),
+        expected_outcome: None,
+    };
+    for result in [
+        client
+            .update_school_im_thinking_about(&update)
+            .await
+            .map(|_| ()),
+        client
+            .remove_school_im_thinking_about(9999)
+            .await
+            .map(|_| ()),
+    ] {
+        match result {
+            Err(Error::NotFound { id, .. }) => assert_eq!(id.as_deref(), Some("9999")),
+            other => panic!("{other:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn dry_run_sends_nothing() {
+    let server = MockServer::start().await;
+    let client = server.client_builder().dry_run(true).build().await.unwrap();
+    assert!(client.is_dry_run());
+    let write = client
+        .update_school_im_thinking_about(&SchoolUpdate {
+            id:               101,
+            interest_level:   Some(1),
+            expected_outcome: None,
+        })
+        .await
+        .unwrap();
+    let Write::DryRun(dry_run) = write else {
+        panic!("{write:?}");
+    };
+    assert_eq!(dry_run.method, http::Method::PATCH);
+    assert_eq!(
+        dry_run.url.path(),
+        "/college/colleges-im-thinking-about/101"
+    );
+    assert_eq!(dry_run.body, Some(json!({ "interestLevel": 1 })));
+    assert!(client
+        .remove_school_im_thinking_about(101)
+        .await
+        .unwrap()
+        .is_dry_run());
+    assert!(writes(&server).is_empty());
+    // Reads still go through
+    assert_eq!(
+        client
+            .get_all_schools_im_thinking_about()
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+}